@@ -21,6 +21,9 @@ pub enum Commands {
         /// Persist the directory in the configuration file
         #[arg(short, long)]
         persist: bool,
+        /// Add the root of the git repository containing the directory instead
+        #[arg(long)]
+        root: bool,
     },
     /// Remove a non-existent repository from the configuration file
     Clean,
@@ -36,6 +39,7 @@ pub enum Commands {
 }
 
 #[cfg(test)]
+#[allow(clippy::needless_borrows_for_generic_args)]
 mod tests {
     use super::*;
 
@@ -43,7 +47,7 @@ mod tests {
     fn test_cli_add_with_dir() {
         let cli = Cli::try_parse_from(&["pavo", "add", "/path/to/entry"]).unwrap();
         match cli.command {
-            Some(Commands::Add { dir, persist }) => {
+            Some(Commands::Add { dir, persist, .. }) => {
                 assert_eq!(dir, Some("/path/to/entry".to_string()));
                 assert!(!persist);
             }
@@ -56,7 +60,7 @@ mod tests {
         let cli = Cli::try_parse_from(&["pavo", "add"]).unwrap();
         assert!(cli.command.is_some());
         match cli.command {
-            Some(Commands::Add { dir, persist }) => {
+            Some(Commands::Add { dir, persist, .. }) => {
                 assert_eq!(dir, None);
                 assert!(!persist);
             }
@@ -68,7 +72,7 @@ mod tests {
     fn test_cli_add_with_persist() {
         let cli = Cli::try_parse_from(&["pavo", "add", "/path/to/entry", "--persist"]).unwrap();
         match cli.command {
-            Some(Commands::Add { dir, persist }) => {
+            Some(Commands::Add { dir, persist, .. }) => {
                 assert_eq!(dir, Some("/path/to/entry".to_string()));
                 assert!(persist);
             }
@@ -76,6 +80,18 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_cli_add_with_root() {
+        let cli = Cli::try_parse_from(&["pavo", "add", "--root"]).unwrap();
+        match cli.command {
+            Some(Commands::Add { dir, root, .. }) => {
+                assert_eq!(dir, None);
+                assert!(root);
+            }
+            _ => panic!("Expected Add command"),
+        }
+    }
+
     #[test]
     fn test_cli_init_bash() {
         let cli = Cli::try_parse_from(&["pavo", "init", "bash"]).unwrap();
@@ -1,9 +1,18 @@
 use anyhow::Result;
 use chrono::Duration;
+use ratatui::style::Color;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 
+use crate::entry::directory::GitignoreMode;
+use crate::entry::external::PreviewHandler;
+use crate::entry::file;
+use crate::entry::state::PathState;
+use crate::search::matcher::{CaseMatching, MatcherAlgorithm};
+use crate::search::query::SearchField;
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ConfigPath {
     pub path: PathBuf,
@@ -27,6 +36,280 @@ pub struct Config {
 
     #[serde(default)]
     pub max_unselected_time: u64,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub search: SearchConfig,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub preview: PreviewConfig,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub list: ListConfig,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub theme: ThemeConfig,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub keys: KeysConfig,
+}
+
+/// デフォルト値のままの設定かどうか
+///
+/// デフォルト値を設定ファイルに書き込むと、後からデフォルト値を変えても反映されなくなるため、
+/// 変更していないセクションは保存しない
+fn is_default<T: Default + PartialEq>(value: &T) -> bool {
+    *value == T::default()
+}
+
+/// TUIの検索に関する設定
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SearchConfig {
+    /// 検索語のマッチ対象にするフィールド（先頭から順に評価する）
+    #[serde(default = "default_search_scope")]
+    pub scope: Vec<SearchField>,
+    /// 検索語のマッチアルゴリズム
+    #[serde(default)]
+    pub algorithm: MatcherAlgorithm,
+    /// 大文字小文字の扱い
+    #[serde(default)]
+    pub case: CaseMatching,
+}
+
+impl Default for SearchConfig {
+    fn default() -> Self {
+        Self {
+            scope: default_search_scope(),
+            algorithm: MatcherAlgorithm::default(),
+            case: CaseMatching::default(),
+        }
+    }
+}
+
+fn default_search_scope() -> Vec<SearchField> {
+    vec![SearchField::Display, SearchField::Path, SearchField::Tags]
+}
+
+/// TUIのパス一覧に関する設定
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ListConfig {
+    /// パスの前に表示するアイコンの種類
+    #[serde(default)]
+    pub icons: IconStyle,
+}
+
+/// パス一覧に表示するアイコンの種類
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IconStyle {
+    /// Unicodeの絵文字
+    #[default]
+    Unicode,
+    /// Nerd Fontのグリフ
+    Nerdfont,
+    /// アイコンを表示しない
+    None,
+}
+
+/// 配色のプリセット
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ThemePreset {
+    /// 暗い背景向け
+    #[default]
+    Dark,
+    /// 明るい背景向け
+    Light,
+    /// 明るい色だけを使うハイコントラスト
+    HighContrast,
+}
+
+impl ThemePreset {
+    /// `bat_theme`を省略した時にファイルのプレビューに使うbatのテーマ
+    ///
+    /// 明るい背景ではbatのデフォルトのテーマが読みにくいため、明るいテーマを使う
+    pub fn bat_theme(self) -> Option<&'static str> {
+        match self {
+            Self::Light => Some("GitHub"),
+            Self::Dark | Self::HighContrast => None,
+        }
+    }
+}
+
+/// TUIの配色に関する設定
+///
+/// 要素ごとの色を指定すると、プリセットの色を上書きする
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ThemeConfig {
+    /// 配色のプリセット
+    #[serde(default)]
+    pub preset: ThemePreset,
+    /// フォーカス中のパネルの枠
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub focus: Option<Color>,
+    /// フォーカスしていないパネルの枠
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub border: Option<Color>,
+    /// 検索語にマッチした文字
+    #[serde(default, rename = "match", skip_serializing_if = "Option::is_none")]
+    pub matched: Option<Color>,
+    /// 選択中の行の文字
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub selection_fg: Option<Color>,
+    /// 選択中の行の背景
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub selection_bg: Option<Color>,
+    /// 検索の入力欄の文字
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input: Option<Color>,
+    /// モーダルの枠と文字
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub modal: Option<Color>,
+    /// ワークツリーの行や開けないパスなど、暗く表示する行
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dim: Option<Color>,
+    /// エントリの種類のバッジ
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub badge: Option<Color>,
+    /// `[missing]`などの開けないパスの状態
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<Color>,
+}
+
+/// キーの割り当ての方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KeyMode {
+    /// 矢印キーとCtrlキーで操作する
+    #[default]
+    Default,
+    /// PathsとPreviewパネルをvimのノーマルモード、Searchパネルを挿入モードとして操作する
+    Vim,
+}
+
+/// 設定ファイルの`[keys]`セクション
+///
+/// 範囲ごとにキーの表記と操作の名前の対応を指定し、組み込みの割り当てを上書きする
+/// （キーと操作の名前はTUIの起動時に確認する）
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct KeysConfig {
+    /// 組み込みの割り当ての方式
+    #[serde(default)]
+    pub mode: KeyMode,
+    /// 全てのパネルで使うキー
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub global: BTreeMap<String, String>,
+    /// Searchパネルで使うキー
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub search: BTreeMap<String, String>,
+    /// Pathsパネルで使うキー
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub paths: BTreeMap<String, String>,
+    /// Previewパネルで使うキー
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub preview: BTreeMap<String, String>,
+    /// パスの設定モーダルで使うキー
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub modal: BTreeMap<String, String>,
+}
+
+/// プレビューに関する設定
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct PreviewConfig {
+    /// ディレクトリツリーの表示階層数
+    #[serde(default = "default_tree_depth")]
+    pub tree_depth: usize,
+    /// ディレクトリツリーの最大表示エントリ数
+    #[serde(default = "default_max_entries")]
+    pub max_entries: usize,
+    /// ファイルの表示行数
+    #[serde(default = "default_file_lines")]
+    pub file_lines: usize,
+    /// ドットファイルを表示するかどうか
+    #[serde(default)]
+    pub show_hidden: bool,
+    /// batのテーマ名（省略時はbatのデフォルト）
+    #[serde(default)]
+    pub bat_theme: Option<String>,
+    /// ファイルのプレビューに行番号を表示するかどうか
+    #[serde(default)]
+    pub line_numbers: bool,
+    /// リポジトリのプレビューで`.gitignore`に一致するエントリの扱い
+    #[serde(default)]
+    pub gitignore: GitignoreMode,
+    /// リポジトリのプレビューに表示するコミット数
+    #[serde(default = "default_commit_count")]
+    pub commit_count: usize,
+    /// コミットログにブランチのグラフを表示するかどうか
+    #[serde(default)]
+    pub commit_graph: bool,
+    /// ディレクトリのツリーの下にREADMEを表示するかどうか
+    #[serde(default = "default_readme")]
+    pub readme: bool,
+    /// READMEの表示行数
+    #[serde(default = "default_readme_lines")]
+    pub readme_lines: usize,
+    /// プレビューの先頭にパスのメタデータとブックマークの統計を表示するかどうか
+    #[serde(default = "default_metadata")]
+    pub metadata: bool,
+    /// ディレクトリのプレビューにプロジェクトの概要（マニフェストの内容）を表示するかどうか
+    #[serde(default = "default_project_summary")]
+    pub project_summary: bool,
+    /// ファイルの種類ごとにプレビューを生成する外部コマンド（一致しない場合は組み込みのプレビューを使う）
+    #[serde(default)]
+    pub handlers: Vec<PreviewHandler>,
+}
+
+impl Default for PreviewConfig {
+    fn default() -> Self {
+        Self {
+            tree_depth: default_tree_depth(),
+            max_entries: default_max_entries(),
+            file_lines: default_file_lines(),
+            show_hidden: false,
+            bat_theme: None,
+            line_numbers: false,
+            gitignore: GitignoreMode::default(),
+            commit_count: default_commit_count(),
+            commit_graph: false,
+            readme: default_readme(),
+            readme_lines: default_readme_lines(),
+            metadata: default_metadata(),
+            project_summary: default_project_summary(),
+            handlers: Vec::new(),
+        }
+    }
+}
+
+fn default_readme() -> bool {
+    true
+}
+
+fn default_readme_lines() -> usize {
+    20
+}
+
+fn default_metadata() -> bool {
+    true
+}
+
+fn default_project_summary() -> bool {
+    true
+}
+
+fn default_commit_count() -> usize {
+    5
+}
+
+fn default_tree_depth() -> usize {
+    1
+}
+
+fn default_max_entries() -> usize {
+    128
+}
+
+fn default_file_lines() -> usize {
+    10
 }
 
 impl Default for Config {
@@ -35,6 +318,11 @@ impl Default for Config {
             paths: Vec::new(),
             auto_clean: true,
             max_unselected_time: Duration::days(7).num_seconds() as u64,
+            search: SearchConfig::default(),
+            preview: PreviewConfig::default(),
+            list: ListConfig::default(),
+            theme: ThemeConfig::default(),
+            keys: KeysConfig::default(),
         }
     }
 }
@@ -60,6 +348,15 @@ impl Config {
 
         let content = fs::read_to_string(&config_file)?;
         let config: Config = toml::from_str(&content)?;
+        if let Some(theme) = &config.preview.bat_theme {
+            if !file::is_bat_theme(theme) {
+                anyhow::bail!(
+                    "Unknown bat_theme `{}` in [preview] of {} (see `bat --list-themes`)",
+                    theme,
+                    config_file.display()
+                );
+            }
+        }
         Ok(config)
     }
 
@@ -88,9 +385,12 @@ impl Config {
         Ok(())
     }
 
+    /// 存在しないパスを削除する
+    ///
+    /// マウントされていないボリュームや権限のないパスは、後で開けるようになるため残す
     pub fn remove_nonexistent_paths(&mut self) {
         self.paths.retain(|config_path| {
-            let exists = config_path.path.exists();
+            let exists = PathState::detect(&config_path.path) != PathState::Missing;
             if !exists {
                 println!(
                     "{} does not exist, so it is deleted.",
@@ -113,6 +413,15 @@ impl Config {
     pub fn contains(&self, path: &Path) -> bool {
         self.paths.iter().any(|p| p.path == path)
     }
+
+    /// TUIのプレビューに使う設定（`bat_theme`の省略時は配色のプリセットに合わせる）
+    pub fn preview_config(&self) -> PreviewConfig {
+        let mut preview = self.preview.clone();
+        if preview.bat_theme.is_none() {
+            preview.bat_theme = self.theme.preset.bat_theme().map(str::to_string);
+        }
+        preview
+    }
 }
 
 #[cfg(test)]
@@ -131,6 +440,10 @@ mod tests {
             config.max_unselected_time,
             Duration::days(7).num_seconds() as u64
         );
+        assert_eq!(
+            config.search.scope,
+            vec![SearchField::Display, SearchField::Path, SearchField::Tags]
+        );
     }
 
     #[test]
@@ -192,6 +505,27 @@ mod tests {
         assert_eq!(config.paths[0].path, temp_dir.path());
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn test_remove_nonexistent_paths_should_keep_unreadable_paths() {
+        let temp_dir = tempdir().unwrap();
+        let link = temp_dir.path().join("loop");
+        std::os::unix::fs::symlink(&link, &link).unwrap();
+        let mut config = Config::default();
+        config.paths.push(ConfigPath {
+            path: link.clone(),
+            last_selected: chrono::Utc::now(),
+            persist: false,
+            tags: Vec::new(),
+            access_count: 0,
+        });
+
+        config.remove_nonexistent_paths();
+
+        assert_eq!(config.paths.len(), 1);
+        assert_eq!(config.paths[0].path, link);
+    }
+
     #[test]
     fn test_remove_nonexistent_paths_should_keep_persisted_paths() {
         let deleted_dir = tempdir().unwrap();
@@ -365,4 +699,280 @@ mod tests {
         assert!(!loaded_config.auto_clean);
         assert_eq!(loaded_config.max_unselected_time, 3600);
     }
+
+    #[test]
+    fn test_search_設定ファイルから読み込まれる() {
+        // Arrange
+        let content = r#"
+            [search]
+            scope = ["display", "path", "tags"]
+            algorithm = "regex"
+            case = "respect"
+        "#;
+
+        // Act
+        let config: Config = toml::from_str(content).unwrap();
+
+        // Assert
+        assert_eq!(
+            config.search.scope,
+            vec![SearchField::Display, SearchField::Path, SearchField::Tags]
+        );
+        assert_eq!(config.search.algorithm, MatcherAlgorithm::Regex);
+        assert_eq!(config.search.case, CaseMatching::Respect);
+    }
+
+    #[test]
+    fn test_search_省略時はデフォルト値が使われる() {
+        // Act
+        let config: Config = toml::from_str("auto_clean = true").unwrap();
+
+        // Assert
+        assert_eq!(config.search, SearchConfig::default());
+    }
+
+    #[test]
+    fn test_preview_設定ファイルから読み込まれる() {
+        // Arrange
+        let content = r#"
+            [preview]
+            tree_depth = 2
+            max_entries = 50
+            file_lines = 30
+            show_hidden = true
+            bat_theme = "Nord"
+            line_numbers = true
+            gitignore = "mark"
+            commit_count = 10
+            commit_graph = true
+            readme = false
+            readme_lines = 5
+            metadata = false
+            project_summary = false
+        "#;
+
+        // Act
+        let config: Config = toml::from_str(content).unwrap();
+
+        // Assert
+        assert_eq!(
+            config.preview,
+            PreviewConfig {
+                tree_depth: 2,
+                max_entries: 50,
+                file_lines: 30,
+                show_hidden: true,
+                bat_theme: Some("Nord".to_string()),
+                line_numbers: true,
+                gitignore: GitignoreMode::Mark,
+                commit_count: 10,
+                commit_graph: true,
+                readme: false,
+                readme_lines: 5,
+                metadata: false,
+                project_summary: false,
+                handlers: Vec::new(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_preview_一部のみ指定した場合は残りがデフォルト値になる() {
+        // Act
+        let config: Config = toml::from_str("[preview]\nfile_lines = 20").unwrap();
+
+        // Assert
+        assert_eq!(config.preview.file_lines, 20);
+        assert_eq!(config.preview.tree_depth, 1);
+        assert_eq!(config.preview.max_entries, 128);
+        assert!(!config.preview.show_hidden);
+    }
+
+    #[test]
+    fn test_preview_handlersが読み込まれる() {
+        // Arrange
+        let content = r#"
+            [[preview.handlers]]
+            glob = "*.json"
+            command = "jq -C . {}"
+
+            [[preview.handlers]]
+            mime = "image/*"
+            command = "exiftool"
+            timeout_ms = 3000
+        "#;
+
+        // Act
+        let config: Config = toml::from_str(content).unwrap();
+
+        // Assert
+        let handlers = &config.preview.handlers;
+        assert_eq!(handlers.len(), 2);
+        assert_eq!(
+            handlers[0].glob.as_ref().map(|glob| glob.as_str()),
+            Some("*.json")
+        );
+        assert_eq!(handlers[0].command, "jq -C . {}");
+        assert_eq!(handlers[0].timeout_ms, 1000);
+        assert_eq!(handlers[1].mime.as_deref(), Some("image/*"));
+        assert_eq!(handlers[1].timeout_ms, 3000);
+    }
+
+    #[test]
+    fn test_preview_handlersの不正なglobは読み込みエラーになる() {
+        // Arrange
+        let content = r#"
+            [[preview.handlers]]
+            glob = "*.{json"
+            command = "jq -C . {}"
+        "#;
+
+        // Act
+        let result = toml::from_str::<Config>(content);
+
+        // Assert
+        let err = result.unwrap_err().to_string();
+        assert!(err.contains("invalid glob `*.{json`"), "{}", err);
+    }
+
+    #[test]
+    fn test_preview_handlersの不明なkindは読み込みエラーになる() {
+        // Arrange
+        let content = r#"
+            [[preview.handlers]]
+            kind = "repository"
+            command = "onefetch"
+
+            [[preview.handlers]]
+            kind = "folder"
+            command = "eza --tree"
+        "#;
+
+        // Act
+        let result = toml::from_str::<Config>(content);
+
+        // Assert
+        let err = result.unwrap_err().to_string();
+        assert!(err.contains("unknown kind `folder`"), "{}", err);
+    }
+
+    #[test]
+    fn test_list_アイコンの種類が読み込まれる() {
+        // Arrange
+        let content = r#"
+            [list]
+            icons = "nerdfont"
+        "#;
+
+        // Act
+        let config: Config = toml::from_str(content).unwrap();
+
+        // Assert
+        assert_eq!(config.list.icons, IconStyle::Nerdfont);
+        assert_eq!(Config::default().list.icons, IconStyle::Unicode);
+    }
+
+    #[test]
+    fn test_theme_プリセットと要素ごとの色が読み込まれる() {
+        // Arrange
+        let content = r##"
+            [theme]
+            preset = "high-contrast"
+            match = "#ff8800"
+            focus = "light-blue"
+        "##;
+
+        // Act
+        let config: Config = toml::from_str(content).unwrap();
+
+        // Assert
+        assert_eq!(config.theme.preset, ThemePreset::HighContrast);
+        assert_eq!(config.theme.matched, Some(Color::Rgb(0xff, 0x88, 0x00)));
+        assert_eq!(config.theme.focus, Some(Color::LightBlue));
+        assert_eq!(config.theme.border, None);
+    }
+
+    #[test]
+    fn test_preview_config_明るいプリセットでは明るいbatのテーマを使う() {
+        // Arrange
+        let light: Config = toml::from_str("[theme]\npreset = \"light\"").unwrap();
+        let explicit: Config =
+            toml::from_str("[theme]\npreset = \"light\"\n[preview]\nbat_theme = \"Nord\"").unwrap();
+        let dark = Config::default();
+
+        // Act & Assert
+        assert_eq!(light.preview_config().bat_theme, Some("GitHub".to_string()));
+        assert_eq!(
+            explicit.preview_config().bat_theme,
+            Some("Nord".to_string())
+        );
+        assert_eq!(dark.preview_config().bat_theme, None);
+    }
+
+    #[test]
+    fn test_new_不明なbat_themeは読み込みエラーになる() {
+        // Arrange
+        let temp_dir = tempdir().unwrap();
+        fs::write(
+            temp_dir.path().join("pavo.toml"),
+            "[preview]\nbat_theme = \"NoSuchTheme\"\n",
+        )
+        .unwrap();
+
+        // Act
+        let result = Config::new(Some(temp_dir.path().to_path_buf()));
+
+        // Assert
+        let err = result.unwrap_err().to_string();
+        assert!(err.contains("Unknown bat_theme `NoSuchTheme`"), "{}", err);
+    }
+
+    #[test]
+    fn test_bat_theme_プリセットのbatのテーマは組み込みのテーマである() {
+        // Arrange
+        let presets = [
+            ThemePreset::Dark,
+            ThemePreset::Light,
+            ThemePreset::HighContrast,
+        ];
+
+        // Act & Assert
+        for theme in presets.iter().filter_map(|preset| preset.bat_theme()) {
+            assert!(file::is_bat_theme(theme), "{}", theme);
+        }
+    }
+
+    #[test]
+    fn test_preview_デフォルト設定を保存して読み込める() {
+        // Arrange
+        let temp_dir = tempdir().unwrap();
+        let config_file = temp_dir.path().join("pavo.toml");
+
+        // Act
+        Config::default().save(&config_file).unwrap();
+        let content = fs::read_to_string(&config_file).unwrap();
+        let loaded: Config = toml::from_str(&content).unwrap();
+
+        // Assert
+        assert_eq!(loaded.preview, PreviewConfig::default());
+    }
+
+    #[test]
+    fn test_save_デフォルト値のままのセクションは書き込まない() {
+        // Arrange
+        let temp_dir = tempdir().unwrap();
+        let config_file = temp_dir.path().join("pavo.toml");
+        let mut config = Config::default();
+        config.theme.preset = ThemePreset::Light;
+
+        // Act
+        config.save(&config_file).unwrap();
+        let content = fs::read_to_string(&config_file).unwrap();
+
+        // Assert
+        assert!(content.contains("[theme]"), "{}", content);
+        for section in ["[search]", "[preview]", "[list]", "[keys]"] {
+            assert!(!content.contains(section), "{}", content);
+        }
+    }
 }
@@ -1,9 +1,117 @@
+pub mod archive;
 pub mod directory;
+pub mod external;
 pub mod file;
+pub mod metadata;
+pub mod project;
+pub mod readme;
+pub mod registry;
 pub mod repository;
+pub mod state;
 
-use ratatui::text::Line;
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+};
+use std::path::Path;
 
 pub trait Entry {
     fn get_preview(&self) -> Vec<Line<'static>>;
+
+    /// 種類（`directory`、`repository`など）
+    fn kind(&self) -> EntryKind;
+
+    /// 種類を表すアイコン
+    fn icon(&self) -> &'static str;
+
+    /// 種類を補足するバッジ（アーカイブの形式など）
+    fn badges(&self) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+/// プレビューを生成できなかった理由を表す行
+pub fn error_line(message: String) -> Line<'static> {
+    Line::from(vec![
+        Span::raw("⚠️ "),
+        Span::styled(
+            message,
+            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+        ),
+    ])
+}
+
+/// エントリの種類
+///
+/// レジストリに登録された種類の名前で識別する（組み込みの種類は定数として定義している）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct EntryKind(&'static str);
+
+impl EntryKind {
+    pub const DIRECTORY: Self = Self("directory");
+    pub const REPOSITORY: Self = Self("repository");
+    pub const FILE: Self = Self("file");
+    pub const ARCHIVE: Self = Self("archive");
+
+    /// 名前から種類を作成する（レジストリに独自の種類を登録する場合に使う）
+    pub const fn new(name: &'static str) -> Self {
+        Self(name)
+    }
+
+    /// パスからエントリの種類を判定する（どの種類にも一致しない場合はファイルとする）
+    pub fn detect(path: &Path) -> Self {
+        registry::registry()
+            .detect(path)
+            .map_or(Self::FILE, |registration| registration.kind)
+    }
+
+    /// 検索クエリの`kind:`などで使用する名前（または別名）から種類を取得する
+    pub fn from_name(name: &str) -> Option<Self> {
+        registry::registry()
+            .find(name)
+            .map(|registration| registration.kind)
+    }
+
+    /// 種類の名前を取得する
+    pub fn name(self) -> &'static str {
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helper::setup_test_repo;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_detect_ディレクトリ_リポジトリ_ファイル_アーカイブを判定する() {
+        // Arrange
+        let dir = tempdir().unwrap();
+        let repo_dir = tempdir().unwrap();
+        setup_test_repo(&repo_dir);
+        let file = dir.path().join("file.txt");
+        std::fs::write(&file, "").unwrap();
+        let archive = dir.path().join("release.zip");
+        std::fs::write(&archive, "").unwrap();
+
+        // Act & Assert
+        assert_eq!(EntryKind::detect(dir.path()), EntryKind::DIRECTORY);
+        assert_eq!(EntryKind::detect(repo_dir.path()), EntryKind::REPOSITORY);
+        assert_eq!(EntryKind::detect(&file), EntryKind::FILE);
+        assert_eq!(EntryKind::detect(&archive), EntryKind::ARCHIVE);
+    }
+
+    #[test]
+    fn test_from_name_別名と短い名前を受け付ける() {
+        assert_eq!(EntryKind::from_name("repo"), Some(EntryKind::REPOSITORY));
+        assert_eq!(
+            EntryKind::from_name("repository"),
+            Some(EntryKind::REPOSITORY)
+        );
+        assert_eq!(EntryKind::from_name("dir"), Some(EntryKind::DIRECTORY));
+        assert_eq!(EntryKind::from_name("file"), Some(EntryKind::FILE));
+        assert_eq!(EntryKind::from_name("archive"), Some(EntryKind::ARCHIVE));
+        assert_eq!(EntryKind::from_name("unknown"), None);
+    }
 }
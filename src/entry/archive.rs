@@ -0,0 +1,450 @@
+use flate2::read::GzDecoder;
+use ratatui::{
+    style::{Color, Style},
+    text::{Line, Span},
+};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{self, BufReader, Read};
+use std::path::{Path, PathBuf};
+
+use super::file::format_size;
+use super::{error_line, Entry, EntryKind};
+use crate::config::PreviewConfig;
+
+/// 一覧を作るために読み込むメンバーの最大数
+///
+/// tarはメンバーを順に読み進めるしかないため、巨大なアーカイブでも途中で打ち切る
+const MAX_MEMBERS: usize = 10_000;
+
+/// 一覧を作るために読み込むtarの最大バイト数（圧縮されている場合は展開後のサイズ）
+///
+/// tarはメンバーの中身も読み飛ばす必要があるため、巨大なメンバーを含むアーカイブで
+/// プレビューのワーカースレッドが長時間止まらないよう途中で打ち切る
+const MAX_TAR_BYTES: u64 = 64 * 1024 * 1024;
+
+/// アーカイブの形式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    Zip,
+    Tar,
+    TarGz,
+}
+
+impl ArchiveFormat {
+    /// ファイル名の拡張子からアーカイブの形式を判定する
+    ///
+    /// xz・bzip2・zstdで圧縮したtarは展開できないため、アーカイブとして扱わない
+    pub fn detect(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_string_lossy().to_lowercase();
+        if name.ends_with(".zip") {
+            Some(Self::Zip)
+        } else if name.ends_with(".tar") {
+            Some(Self::Tar)
+        } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            Some(Self::TarGz)
+        } else {
+            None
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Zip => "zip",
+            Self::Tar => "tar",
+            Self::TarGz => "tar.gz",
+        }
+    }
+}
+
+/// アーカイブのメンバー
+struct Member {
+    path: String,
+    size: u64,
+    is_dir: bool,
+}
+
+/// メンバーのパスから組み立てたツリーのノード
+#[derive(Default)]
+struct Node {
+    children: BTreeMap<String, Node>,
+    /// ファイルの場合のサイズ（ディレクトリの場合は`None`）
+    size: Option<u64>,
+}
+
+impl Node {
+    fn insert(&mut self, member: &Member) {
+        let components: Vec<&str> = member
+            .path
+            .split('/')
+            .filter(|component| !component.is_empty() && *component != ".")
+            .collect();
+        let Some((last, parents)) = components.split_last() else {
+            return;
+        };
+        let mut node = self;
+        for component in parents {
+            node = node.children.entry(component.to_string()).or_default();
+        }
+        let node = node.children.entry(last.to_string()).or_default();
+        if !member.is_dir {
+            node.size = Some(member.size);
+        }
+    }
+
+    fn is_dir(&self) -> bool {
+        self.size.is_none()
+    }
+}
+
+/// zipやtarのメンバーをツリーで表示するエントリ
+pub struct ArchiveEntry {
+    path: PathBuf,
+    format: ArchiveFormat,
+    max_depth: usize,
+    max_entries: usize,
+    show_hidden: bool,
+}
+
+impl ArchiveEntry {
+    pub const ICON: &'static str = "📦";
+
+    pub fn new(path: PathBuf, format: ArchiveFormat) -> Self {
+        Self::from_config(path, format, &PreviewConfig::default())
+    }
+
+    /// プレビューの設定からArchiveEntryを作成する
+    pub fn from_config(path: PathBuf, format: ArchiveFormat, config: &PreviewConfig) -> Self {
+        Self {
+            path,
+            format,
+            max_depth: config.tree_depth,
+            max_entries: config.max_entries,
+            show_hidden: config.show_hidden,
+        }
+    }
+
+    /// メンバーの一覧を読み込む（上限に達した場合は`true`を返す）
+    fn read_members(&self) -> io::Result<(Vec<Member>, bool)> {
+        let file = File::open(&self.path)?;
+        match self.format {
+            ArchiveFormat::Zip => read_zip_members(file),
+            ArchiveFormat::Tar => read_tar_members(BufReader::new(file), MAX_TAR_BYTES),
+            ArchiveFormat::TarGz => {
+                read_tar_members(GzDecoder::new(BufReader::new(file)), MAX_TAR_BYTES)
+            }
+        }
+    }
+
+    fn build_tree(
+        &self,
+        node: &Node,
+        prefix: &str,
+        output: &mut Vec<Line<'static>>,
+        current_depth: usize,
+        entries_count: &mut usize,
+    ) -> bool {
+        let children: Vec<_> = node
+            .children
+            .iter()
+            .filter(|(name, _)| self.show_hidden || !name.starts_with('.'))
+            .collect();
+
+        for (i, (name, child)) in children.iter().enumerate() {
+            if *entries_count >= self.max_entries {
+                output.push(Line::from(vec![
+                    Span::raw(prefix.to_string()),
+                    Span::raw("└── ..."),
+                ]));
+                return true;
+            }
+
+            let is_last = i == children.len() - 1;
+            let mut spans = Vec::new();
+            if current_depth > 0 {
+                spans.push(Span::raw(prefix.to_string()));
+                spans.push(Span::raw(if is_last { "└── " } else { "├── " }));
+            }
+            match child.size {
+                None => spans.push(Span::styled(
+                    name.to_string(),
+                    Style::default().fg(Color::Green),
+                )),
+                Some(size) => {
+                    spans.push(Span::raw(name.to_string()));
+                    spans.push(Span::styled(
+                        format!(" ({})", format_size(size)),
+                        Style::default().fg(Color::DarkGray),
+                    ));
+                }
+            }
+            output.push(Line::from(spans));
+            *entries_count += 1;
+
+            if child.is_dir() && current_depth < self.max_depth {
+                let new_prefix = if current_depth == 0 {
+                    String::new()
+                } else {
+                    format!("{}{}", prefix, if is_last { "    " } else { "│   " })
+                };
+                if self.build_tree(child, &new_prefix, output, current_depth + 1, entries_count) {
+                    return true;
+                }
+            }
+        }
+        false
+    }
+}
+
+impl Entry for ArchiveEntry {
+    fn get_preview(&self) -> Vec<Line<'static>> {
+        let (members, truncated) = match self.read_members() {
+            Ok(result) => result,
+            Err(err) => return vec![error_line(format!("Cannot read archive: {}", err))],
+        };
+
+        let files: Vec<_> = members.iter().filter(|member| !member.is_dir).collect();
+        let total_size: u64 = files.iter().map(|member| member.size).sum();
+        let mut root = Node::default();
+        for member in &members {
+            root.insert(member);
+        }
+
+        let more = if truncated { "+" } else { "" };
+        let mut preview = vec![
+            Line::from(vec![
+                Span::styled(
+                    format!("📦 Archive ({}): ", self.format.name()),
+                    Style::default().fg(Color::Yellow),
+                ),
+                Span::raw(format!(
+                    "{}{} files, {}{}",
+                    files.len(),
+                    more,
+                    format_size(total_size),
+                    more
+                )),
+            ]),
+            Line::from(""),
+        ];
+        let mut entries_count = 0;
+        self.build_tree(&root, "", &mut preview, 0, &mut entries_count);
+        preview
+    }
+
+    fn kind(&self) -> EntryKind {
+        EntryKind::ARCHIVE
+    }
+
+    fn icon(&self) -> &'static str {
+        Self::ICON
+    }
+
+    fn badges(&self) -> Vec<String> {
+        vec![self.format.name().to_string()]
+    }
+}
+
+fn read_zip_members(file: File) -> io::Result<(Vec<Member>, bool)> {
+    let mut archive = zip::ZipArchive::new(BufReader::new(file)).map_err(io::Error::other)?;
+    let count = archive.len().min(MAX_MEMBERS);
+    let mut members = Vec::with_capacity(count);
+    for i in 0..count {
+        // 中身は展開しないため、圧縮方式に関わらず読める
+        let member = archive.by_index_raw(i).map_err(io::Error::other)?;
+        members.push(Member {
+            path: member.name().to_string(),
+            size: member.size(),
+            is_dir: member.is_dir(),
+        });
+    }
+    Ok((members, archive.len() > MAX_MEMBERS))
+}
+
+fn read_tar_members<R: Read>(reader: R, max_bytes: u64) -> io::Result<(Vec<Member>, bool)> {
+    let mut archive = tar::Archive::new(reader.take(max_bytes));
+    let mut members = Vec::new();
+    let mut result = Ok(false);
+    for entry in archive.entries()? {
+        if members.len() >= MAX_MEMBERS {
+            return Ok((members, true));
+        }
+        let member = entry.and_then(|entry| {
+            let header = entry.header();
+            Ok(Member {
+                path: entry.path()?.to_string_lossy().into_owned(),
+                size: header.size()?,
+                is_dir: header.entry_type().is_dir(),
+            })
+        });
+        match member {
+            Ok(member) => members.push(member),
+            Err(err) => {
+                result = Err(err);
+                break;
+            }
+        }
+    }
+    // 上限まで読んだ場合は、途中で切れたことによるエラーも打ち切りとして扱う
+    if archive.into_inner().limit() == 0 {
+        return Ok((members, true));
+    }
+    result.map(|truncated| (members, truncated))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helper::lines_to_string;
+    use flate2::{write::GzEncoder, Compression};
+    use std::io::Write;
+    use tempfile::tempdir;
+
+    fn write_tar<W: Write>(writer: W) -> W {
+        let mut builder = tar::Builder::new(writer);
+        for (path, content) in [
+            ("release/bin/app", &b"binary"[..]),
+            ("release/README.md", &b"# Release"[..]),
+            ("release/.hidden", &b""[..]),
+        ] {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(content.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder.append_data(&mut header, path, content).unwrap();
+        }
+        builder.into_inner().unwrap()
+    }
+
+    fn config(tree_depth: usize) -> PreviewConfig {
+        PreviewConfig {
+            tree_depth,
+            ..PreviewConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_detect_archive_format() {
+        assert_eq!(
+            ArchiveFormat::detect(Path::new("a.zip")),
+            Some(ArchiveFormat::Zip)
+        );
+        assert_eq!(
+            ArchiveFormat::detect(Path::new("a.tar")),
+            Some(ArchiveFormat::Tar)
+        );
+        assert_eq!(
+            ArchiveFormat::detect(Path::new("a-1.0.TAR.GZ")),
+            Some(ArchiveFormat::TarGz)
+        );
+        assert_eq!(
+            ArchiveFormat::detect(Path::new("a.tgz")),
+            Some(ArchiveFormat::TarGz)
+        );
+        assert_eq!(ArchiveFormat::detect(Path::new("a.gz")), None);
+        assert_eq!(ArchiveFormat::detect(Path::new("a.tar.xz")), None);
+        assert_eq!(ArchiveFormat::detect(Path::new("a.tar.bz2")), None);
+        assert_eq!(ArchiveFormat::detect(Path::new("a.tar.zst")), None);
+        assert_eq!(ArchiveFormat::detect(Path::new("main.rs")), None);
+    }
+
+    #[test]
+    fn test_tar_preview_lists_members_as_tree() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("release.tar");
+        write_tar(File::create(&path).unwrap());
+
+        let entry = ArchiveEntry::from_config(path, ArchiveFormat::Tar, &config(2));
+        let preview = lines_to_string(&entry.get_preview());
+
+        assert!(preview.contains("📦 Archive (tar): 3 files, 15 B"));
+        assert!(preview.contains("release"));
+        assert!(preview.contains("├── README.md (9 B)"));
+        assert!(preview.contains("└── bin"));
+        assert!(preview.contains("    └── app (6 B)"));
+        assert!(!preview.contains(".hidden"));
+    }
+
+    #[test]
+    fn test_tar_gz_preview_respects_depth() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("release.tar.gz");
+        let encoder = write_tar(GzEncoder::new(
+            File::create(&path).unwrap(),
+            Compression::default(),
+        ));
+        encoder.finish().unwrap();
+
+        let entry = ArchiveEntry::from_config(path, ArchiveFormat::TarGz, &config(1));
+        let preview = lines_to_string(&entry.get_preview());
+
+        assert!(preview.contains("📦 Archive (tar.gz)"));
+        assert!(preview.contains("└── bin"));
+        assert!(!preview.contains("app"));
+    }
+
+    #[test]
+    fn test_zip_preview_lists_members() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("release.zip");
+        let mut writer = zip::ZipWriter::new(File::create(&path).unwrap());
+        let options = zip::write::SimpleFileOptions::default()
+            .compression_method(zip::CompressionMethod::Stored);
+        writer.add_directory("docs/", options).unwrap();
+        writer.start_file("docs/guide.txt", options).unwrap();
+        writer.write_all(b"guide").unwrap();
+        writer.start_file("LICENSE", options).unwrap();
+        writer.write_all(&[b'x'; 2048]).unwrap();
+        writer.finish().unwrap();
+
+        let entry = ArchiveEntry::from_config(path, ArchiveFormat::Zip, &config(1));
+        let preview = lines_to_string(&entry.get_preview());
+
+        assert!(preview.contains("📦 Archive (zip): 2 files"));
+        assert!(preview.contains("LICENSE (2.0 KiB)"));
+        assert!(preview.contains("docs"));
+        assert!(preview.contains("└── guide.txt (5 B)"));
+    }
+
+    #[test]
+    fn test_max_entries_truncates_tree() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("release.tar");
+        write_tar(File::create(&path).unwrap());
+
+        let mut config = config(2);
+        config.max_entries = 2;
+        let entry = ArchiveEntry::from_config(path, ArchiveFormat::Tar, &config);
+        let preview = lines_to_string(&entry.get_preview());
+
+        assert!(preview.contains("README.md"));
+        assert!(preview.contains("└── ..."));
+        assert!(!preview.contains("app"));
+    }
+
+    #[test]
+    fn test_tar_scan_stops_at_byte_limit() {
+        let tar = write_tar(Vec::new());
+
+        let (all, truncated) = read_tar_members(tar.as_slice(), u64::MAX).unwrap();
+        assert_eq!(all.len(), 3);
+        assert!(!truncated);
+
+        // 最初のメンバー（ヘッダーと中身で1024バイト）の途中で打ち切る
+        let (members, truncated) = read_tar_members(tar.as_slice(), 1500).unwrap();
+        assert!(members.len() < 3);
+        assert!(truncated);
+    }
+
+    #[test]
+    fn test_corrupt_archive_shows_error() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("broken.zip");
+        std::fs::write(&path, "not a zip").unwrap();
+
+        let entry = ArchiveEntry::new(path, ArchiveFormat::Zip);
+        let preview = lines_to_string(&entry.get_preview());
+
+        assert!(preview.contains("Cannot read archive"));
+    }
+}
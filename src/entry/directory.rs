@@ -1,40 +1,110 @@
-use crate::entry::Entry;
+use crate::config::PreviewConfig;
+use crate::entry::{error_line, project, readme, Entry, EntryKind};
+use git2::Repository;
 use ratatui::{
-    style::{Color, Style},
+    style::{Color, Modifier, Style},
     text::{Line, Span},
 };
+use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+/// gitの除外設定（`.gitignore`など）に一致するエントリの扱い
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GitignoreMode {
+    /// 表示しない
+    #[default]
+    Hide,
+    /// 暗く表示し、ディレクトリの中は展開しない
+    Mark,
+    /// 除外設定を無視して全て表示する
+    Show,
+}
 
 pub struct DirectoryEntry {
     path: PathBuf,
     max_depth: usize,
     max_entries: usize,
+    show_hidden: bool,
+    /// 除外設定の判定に使うリポジトリ
+    repo: Option<Repository>,
+    gitignore: GitignoreMode,
+    /// ツリーの下に表示するREADMEの行数（`None`の場合は表示しない）
+    readme_lines: Option<usize>,
+    /// ツリーの上にプロジェクトの概要を表示するかどうか
+    project_summary: bool,
 }
 
 impl DirectoryEntry {
+    pub const ICON: &'static str = "📁";
+
     pub fn new(path: PathBuf, max_entries: Option<usize>, max_depth: Option<usize>) -> Self {
         Self {
             path,
             max_depth: max_depth.unwrap_or(1),
             max_entries: max_entries.unwrap_or(128),
+            show_hidden: false,
+            repo: None,
+            gitignore: GitignoreMode::Show,
+            readme_lines: None,
+            project_summary: false,
+        }
+    }
+
+    /// プレビューの設定からDirectoryEntryを作成する
+    pub fn from_config(path: PathBuf, config: &PreviewConfig) -> Self {
+        Self {
+            path,
+            max_depth: config.tree_depth,
+            max_entries: config.max_entries,
+            show_hidden: config.show_hidden,
+            repo: None,
+            gitignore: GitignoreMode::Show,
+            readme_lines: config.readme.then_some(config.readme_lines),
+            project_summary: config.project_summary,
         }
     }
 
+    /// リポジトリの除外設定（`.gitignore`、`.git/info/exclude`、グローバルな除外設定）に従って表示する
+    ///
+    /// `path`がリポジトリ内にない場合は何もしない
+    pub fn with_gitignore(mut self, mode: GitignoreMode) -> Self {
+        self.gitignore = mode;
+        self.repo = match mode {
+            GitignoreMode::Show => None,
+            GitignoreMode::Hide | GitignoreMode::Mark => Repository::discover(&self.path).ok(),
+        };
+        self
+    }
+
+    /// パスがリポジトリの除外設定に一致するかどうか
+    fn is_ignored(&self, path: &Path) -> bool {
+        let Some(repo) = &self.repo else {
+            return false;
+        };
+        let Some(relative) = repo
+            .workdir()
+            .and_then(|workdir| path.strip_prefix(workdir).ok())
+        else {
+            return false;
+        };
+        repo.is_path_ignored(relative).unwrap_or(false)
+    }
+
     fn build_tree(
+        &self,
         path: &PathBuf,
         prefix: &str,
         output: &mut Vec<Line<'static>>,
         current_depth: usize,
-        max_depth: usize,
-        max_entries: usize,
         entries_count: &mut usize,
     ) -> std::io::Result<bool> {
-        if current_depth > max_depth {
+        if current_depth > self.max_depth {
             return Ok(false);
         }
 
-        if *entries_count >= max_entries {
+        if *entries_count >= self.max_entries {
             let line = Line::from(vec![Span::raw(prefix.to_string()), Span::raw("└── ...")]);
             output.push(line);
             return Ok(true);
@@ -46,18 +116,21 @@ impl DirectoryEntry {
                 entry.ok().and_then(|e| {
                     let name = e.file_name();
                     let name_str = name.to_string_lossy();
-                    if !name_str.starts_with('.') {
-                        Some(e)
-                    } else {
-                        None
+                    if !self.show_hidden && name_str.starts_with('.') {
+                        return None;
+                    }
+                    let ignored = self.is_ignored(&e.path());
+                    if ignored && self.gitignore == GitignoreMode::Hide {
+                        return None;
                     }
+                    Some((e, ignored))
                 })
             })
             .collect::<Vec<_>>();
-        entries.sort_by_key(|entry| entry.path());
+        entries.sort_by_key(|(entry, _)| entry.path());
 
-        for (i, entry) in entries.iter().enumerate() {
-            if *entries_count >= max_entries && i < entries.len() {
+        for (i, (entry, ignored)) in entries.iter().enumerate() {
+            if *entries_count >= self.max_entries && i < entries.len() {
                 let line = Line::from(vec![Span::raw(prefix.to_string()), Span::raw("└── ...")]);
                 output.push(line);
                 return Ok(true);
@@ -66,49 +139,49 @@ impl DirectoryEntry {
             let is_last = i == entries.len() - 1;
             let name = entry.file_name();
             let name_str = name.to_string_lossy().to_string();
-            let is_dir = entry.file_type()?.is_dir();
+            let is_dir = entry.file_type().is_ok_and(|file_type| file_type.is_dir());
+
+            let style = if *ignored {
+                Style::default()
+                    .fg(Color::DarkGray)
+                    .add_modifier(Modifier::ITALIC)
+            } else if is_dir {
+                Style::default().fg(Color::Green)
+            } else {
+                Style::default()
+            };
 
             let line = if current_depth == 0 {
-                if is_dir {
-                    Line::from(Span::styled(name_str, Style::default().fg(Color::Green)))
-                } else {
-                    Line::from(name_str)
-                }
+                Line::from(Span::styled(name_str, style))
             } else {
                 let connector = if is_last { "└── " } else { "├── " };
-                if is_dir {
-                    Line::from(vec![
-                        Span::raw(prefix.to_string()),
-                        Span::raw(connector),
-                        Span::styled(name_str, Style::default().fg(Color::Green)),
-                    ])
-                } else {
-                    Line::from(vec![
-                        Span::raw(prefix.to_string()),
-                        Span::raw(connector),
-                        Span::raw(name_str),
-                    ])
-                }
+                Line::from(vec![
+                    Span::raw(prefix.to_string()),
+                    Span::raw(connector),
+                    Span::styled(name_str, style),
+                ])
             };
             output.push(line);
             *entries_count += 1;
 
-            if is_dir && current_depth < max_depth {
+            // 除外されたディレクトリの中は展開しない
+            if is_dir && !ignored && current_depth < self.max_depth {
                 let new_prefix = if current_depth == 0 {
                     String::new()
                 } else {
                     format!("{}{}", prefix, if is_last { "    " } else { "│   " })
                 };
-                if Self::build_tree(
+                // 読み込めないディレクトリはエラーの行を表示して、残りのエントリを続けて表示する
+                match self.build_tree(
                     &entry.path(),
                     &new_prefix,
                     output,
                     current_depth + 1,
-                    max_depth,
-                    max_entries,
                     entries_count,
-                )? {
-                    break;
+                ) {
+                    Ok(true) => break,
+                    Ok(false) => {}
+                    Err(err) => output.push(unreadable_line(&new_prefix, &err)),
                 }
             }
         }
@@ -116,29 +189,66 @@ impl DirectoryEntry {
     }
 }
 
+/// 読み込めないディレクトリの中身の代わりに表示する行
+fn unreadable_line(prefix: &str, err: &std::io::Error) -> Line<'static> {
+    let reason = match err.kind() {
+        std::io::ErrorKind::PermissionDenied => "permission denied".to_string(),
+        _ => err.to_string(),
+    };
+    Line::from(vec![
+        Span::raw(prefix.to_string()),
+        Span::raw("└── "),
+        Span::styled(format!("({})", reason), Style::default().fg(Color::Red)),
+    ])
+}
+
 impl Entry for DirectoryEntry {
     fn get_preview(&self) -> Vec<Line<'static>> {
-        let mut preview = Vec::new();
+        let mut preview = if self.project_summary {
+            project::render_projects(&self.path)
+        } else {
+            Vec::new()
+        };
         let mut entries_count = 0;
-        Self::build_tree(
-            &self.path,
-            "",
-            &mut preview,
-            0,
-            self.max_depth,
-            self.max_entries,
-            &mut entries_count,
-        )
-        .unwrap();
+        if let Err(err) = self.build_tree(&self.path, "", &mut preview, 0, &mut entries_count) {
+            preview.push(error_line(format!("Cannot read directory: {}", err)));
+        }
+        if let Some(max_lines) = self.readme_lines {
+            if let Some(readme) = readme::find_readme(&self.path) {
+                preview.extend(readme::render_readme(&readme, max_lines));
+            }
+        }
         preview
     }
+
+    fn kind(&self) -> EntryKind {
+        EntryKind::DIRECTORY
+    }
+
+    fn icon(&self) -> &'static str {
+        Self::ICON
+    }
+
+    fn badges(&self) -> Vec<String> {
+        project::project_badges(&self.path)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::test_helper::lines_to_string;
-    use tempfile::tempdir;
+    use crate::test_helper::{lines_to_string, setup_test_repo};
+    use tempfile::{tempdir, TempDir};
+
+    /// `target/`を除外するリポジトリを作成する
+    fn setup_repo_with_ignored_dir() -> TempDir {
+        let dir = TempDir::new().unwrap();
+        setup_test_repo(&dir);
+        fs::write(dir.path().join(".gitignore"), "target/\n").unwrap();
+        fs::create_dir_all(dir.path().join("target").join("debug")).unwrap();
+        fs::write(dir.path().join("target").join("debug").join("app"), "").unwrap();
+        dir
+    }
 
     #[test]
     fn test_should_contain_first_level_children() {
@@ -161,6 +271,36 @@ mod tests {
         assert!(!preview_str.contains("nested_file.txt"));
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn test_should_keep_walking_after_unreadable_directory() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = tempdir().unwrap();
+        let locked = temp_dir.path().join("a_locked");
+        fs::create_dir(&locked).unwrap();
+        fs::write(temp_dir.path().join("b_file.txt"), "").unwrap();
+        fs::set_permissions(&locked, fs::Permissions::from_mode(0o000)).unwrap();
+        // rootなど権限を無視して読める環境では確認できない
+        let readable = fs::read_dir(&locked).is_ok();
+
+        let entry = DirectoryEntry::new(temp_dir.path().to_path_buf(), None, None);
+        let preview_str = lines_to_string(&entry.get_preview());
+        fs::set_permissions(&locked, fs::Permissions::from_mode(0o755)).unwrap();
+
+        if !readable {
+            assert!(preview_str.contains("a_locked\n└── (permission denied)\nb_file.txt"));
+        }
+        assert!(preview_str.contains("b_file.txt"));
+    }
+
+    #[test]
+    fn test_unreadable_line_describes_error() {
+        let denied = std::io::Error::from(std::io::ErrorKind::PermissionDenied);
+        let line = unreadable_line("│   ", &denied);
+        assert_eq!(line.to_string(), "│   └── (permission denied)");
+    }
+
     #[test]
     fn test_should_contain_deeper_children_with_custom_depth() {
         let temp_dir = tempdir().unwrap();
@@ -230,6 +370,51 @@ mod tests {
         assert!(!preview_str.contains(".hidden_dir"));
     }
 
+    #[test]
+    fn test_should_include_dot_files_when_show_hidden_is_enabled() {
+        let temp_dir = tempdir().unwrap();
+        let root_path = temp_dir.path();
+
+        fs::write(root_path.join(".hidden_file"), "").unwrap();
+        fs::write(root_path.join("visible_file.txt"), "").unwrap();
+
+        let config = PreviewConfig {
+            show_hidden: true,
+            ..PreviewConfig::default()
+        };
+        let entry = DirectoryEntry::from_config(root_path.to_path_buf(), &config);
+        let preview = entry.get_preview();
+        let preview_str = lines_to_string(&preview);
+
+        assert!(preview_str.contains(".hidden_file"));
+        assert!(preview_str.contains("visible_file.txt"));
+    }
+
+    #[test]
+    fn test_from_config_should_use_depth_and_entry_limit() {
+        let temp_dir = tempdir().unwrap();
+        let root_path = temp_dir.path();
+        let nested = root_path.join("a").join("b");
+        fs::create_dir_all(&nested).unwrap();
+        fs::write(nested.join("deep.txt"), "").unwrap();
+        for i in 0..5 {
+            fs::write(root_path.join(format!("file{}.txt", i)), "").unwrap();
+        }
+
+        let config = PreviewConfig {
+            tree_depth: 3,
+            max_entries: 4,
+            ..PreviewConfig::default()
+        };
+        let entry = DirectoryEntry::from_config(root_path.to_path_buf(), &config);
+        let preview = entry.get_preview();
+        let preview_str = lines_to_string(&preview);
+
+        assert!(preview_str.contains("deep.txt"));
+        assert!(preview_str.contains("..."));
+        assert!(!preview_str.contains("file4.txt"));
+    }
+
     #[test]
     fn test_should_limit_entries() {
         let temp_dir = tempdir().unwrap();
@@ -378,4 +563,140 @@ mod tests {
         assert!(!preview_str.contains("file3.txt"));
         assert!(!preview_str.contains("file4.txt"));
     }
+
+    #[test]
+    fn test_should_hide_gitignored_entries_in_repository() {
+        let dir = setup_repo_with_ignored_dir();
+
+        let entry = DirectoryEntry::new(dir.path().to_path_buf(), None, None)
+            .with_gitignore(GitignoreMode::Hide);
+        let preview_str = lines_to_string(&entry.get_preview());
+
+        assert!(preview_str.contains("test.txt"));
+        assert!(!preview_str.contains("target"));
+    }
+
+    #[test]
+    fn test_should_hide_entries_excluded_by_info_exclude() {
+        let dir = TempDir::new().unwrap();
+        setup_test_repo(&dir);
+        fs::write(
+            dir.path().join(".git").join("info").join("exclude"),
+            "*.log\n",
+        )
+        .unwrap();
+        fs::write(dir.path().join("debug.log"), "").unwrap();
+
+        let entry = DirectoryEntry::new(dir.path().to_path_buf(), None, None)
+            .with_gitignore(GitignoreMode::Hide);
+        let preview_str = lines_to_string(&entry.get_preview());
+
+        assert!(preview_str.contains("test.txt"));
+        assert!(!preview_str.contains("debug.log"));
+    }
+
+    #[test]
+    fn test_should_mark_gitignored_entries_without_expanding_them() {
+        let dir = setup_repo_with_ignored_dir();
+
+        let entry = DirectoryEntry::new(dir.path().to_path_buf(), None, None)
+            .with_gitignore(GitignoreMode::Mark);
+        let preview = entry.get_preview();
+        let target_line = preview
+            .iter()
+            .find(|line| lines_to_string(std::slice::from_ref(line)) == "target")
+            .unwrap();
+
+        assert_eq!(target_line.spans[0].style.fg, Some(Color::DarkGray));
+        assert!(!lines_to_string(&preview).contains("debug"));
+    }
+
+    #[test]
+    fn test_should_show_gitignored_entries_when_mode_is_show() {
+        let dir = setup_repo_with_ignored_dir();
+
+        let entry = DirectoryEntry::new(dir.path().to_path_buf(), None, None)
+            .with_gitignore(GitignoreMode::Show);
+        let preview_str = lines_to_string(&entry.get_preview());
+
+        assert!(preview_str.contains("target"));
+        assert!(preview_str.contains("debug"));
+    }
+
+    #[test]
+    fn test_with_gitignore_should_be_noop_outside_repository() {
+        let temp_dir = tempdir().unwrap();
+        fs::write(temp_dir.path().join(".gitignore"), "target/\n").unwrap();
+        fs::create_dir_all(temp_dir.path().join("target")).unwrap();
+
+        let entry = DirectoryEntry::new(temp_dir.path().to_path_buf(), None, None)
+            .with_gitignore(GitignoreMode::Hide);
+        let preview_str = lines_to_string(&entry.get_preview());
+
+        assert!(preview_str.contains("target"));
+    }
+
+    #[test]
+    fn test_should_render_readme_below_tree_when_enabled() {
+        let temp_dir = tempdir().unwrap();
+        fs::write(temp_dir.path().join("README.md"), "# Project\n\nHello").unwrap();
+
+        let entry =
+            DirectoryEntry::from_config(temp_dir.path().to_path_buf(), &PreviewConfig::default());
+        let preview_str = lines_to_string(&entry.get_preview());
+
+        let tree = preview_str.find("README.md").unwrap();
+        let heading = preview_str.find("Project").unwrap();
+        assert!(tree < heading);
+        assert!(preview_str.contains("Hello"));
+    }
+
+    #[test]
+    fn test_should_not_render_readme_when_disabled() {
+        let temp_dir = tempdir().unwrap();
+        fs::write(temp_dir.path().join("README.md"), "# Project").unwrap();
+
+        let config = PreviewConfig {
+            readme: false,
+            ..PreviewConfig::default()
+        };
+        let entry = DirectoryEntry::from_config(temp_dir.path().to_path_buf(), &config);
+        let preview_str = lines_to_string(&entry.get_preview());
+
+        assert!(!preview_str.contains("Project"));
+    }
+
+    #[test]
+    fn test_should_render_project_summary_above_tree() {
+        let temp_dir = tempdir().unwrap();
+        fs::write(
+            temp_dir.path().join("Cargo.toml"),
+            "[package]\nname = \"demo\"\nversion = \"1.0.0\"\n",
+        )
+        .unwrap();
+
+        let entry =
+            DirectoryEntry::from_config(temp_dir.path().to_path_buf(), &PreviewConfig::default());
+        let preview_str = lines_to_string(&entry.get_preview());
+
+        let summary = preview_str.find("Package: demo 1.0.0").unwrap();
+        let tree = preview_str.rfind("Cargo.toml").unwrap();
+        assert!(summary < tree);
+        assert_eq!(entry.badges(), vec!["rust".to_string()]);
+    }
+
+    #[test]
+    fn test_should_not_render_project_summary_when_disabled() {
+        let temp_dir = tempdir().unwrap();
+        fs::write(temp_dir.path().join("package.json"), r#"{"name": "web"}"#).unwrap();
+
+        let config = PreviewConfig {
+            project_summary: false,
+            ..PreviewConfig::default()
+        };
+        let entry = DirectoryEntry::from_config(temp_dir.path().to_path_buf(), &config);
+        let preview_str = lines_to_string(&entry.get_preview());
+
+        assert!(!preview_str.contains("Package: web"));
+    }
 }
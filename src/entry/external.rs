@@ -0,0 +1,531 @@
+use ansi_to_tui::IntoText;
+use globset::{Glob, GlobMatcher};
+use ratatui::text::Line;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::hash::{Hash, Hasher};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::str::FromStr;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use super::EntryKind;
+
+/// 外部コマンドの出力を読み込む最大バイト数
+const MAX_OUTPUT_BYTES: u64 = 256 * 1024;
+/// 外部コマンドの終了を確認する間隔
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// パスの種類に応じてプレビューを生成する外部コマンド
+///
+/// `glob`、`mime`、`kind`のうち指定したものが全て一致した場合に使われる
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct PreviewHandler {
+    /// パスに一致させるglobパターン（例: `*.json`）
+    #[serde(default)]
+    pub glob: Option<GlobPattern>,
+    /// 拡張子から推測したMIMEタイプ（例: `application/pdf`、`image/*`）
+    #[serde(default)]
+    pub mime: Option<String>,
+    /// エントリの種類（`file`、`dir`、`repo`、`archive`）
+    #[serde(default, deserialize_with = "deserialize_kind")]
+    pub kind: Option<String>,
+    /// シェルで実行するコマンド（`{}`はパスに置き換え、無い場合は末尾に追加する）
+    ///
+    /// パスは引用符で囲んで埋め込むため、`'{}'`や`"{}"`と書いた場合も`{}`として扱う
+    pub command: String,
+    /// コマンドのタイムアウト（ミリ秒）
+    #[serde(default = "default_timeout_ms")]
+    pub timeout_ms: u64,
+}
+
+fn default_timeout_ms() -> u64 {
+    1000
+}
+
+/// レジストリに登録されていない種類の名前を設定ファイルの読み込みエラーにする
+fn deserialize_kind<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let Some(name) = Option::<String>::deserialize(deserializer)? else {
+        return Ok(None);
+    };
+    match EntryKind::from_name(&name) {
+        Some(_) => Ok(Some(name)),
+        None => Err(serde::de::Error::custom(format!("unknown kind `{}`", name))),
+    }
+}
+
+/// 設定ファイルの読み込み時にコンパイルしたglobパターン
+///
+/// 不正なパターンは設定ファイルの読み込みエラーになる
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct GlobPattern {
+    pattern: String,
+    matcher: GlobMatcher,
+}
+
+impl GlobPattern {
+    pub fn as_str(&self) -> &str {
+        &self.pattern
+    }
+
+    fn is_match(&self, path: &Path) -> bool {
+        self.matcher.is_match(path)
+    }
+}
+
+impl FromStr for GlobPattern {
+    type Err = String;
+
+    fn from_str(pattern: &str) -> Result<Self, Self::Err> {
+        let glob =
+            Glob::new(pattern).map_err(|err| format!("invalid glob `{}`: {}", pattern, err))?;
+        Ok(Self {
+            pattern: pattern.to_string(),
+            matcher: glob.compile_matcher(),
+        })
+    }
+}
+
+impl TryFrom<String> for GlobPattern {
+    type Error = String;
+
+    fn try_from(pattern: String) -> Result<Self, Self::Error> {
+        pattern.parse()
+    }
+}
+
+impl From<GlobPattern> for String {
+    fn from(glob: GlobPattern) -> Self {
+        glob.pattern
+    }
+}
+
+impl PartialEq for GlobPattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.pattern == other.pattern
+    }
+}
+
+impl Eq for GlobPattern {}
+
+impl Hash for GlobPattern {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.pattern.hash(state);
+    }
+}
+
+impl PreviewHandler {
+    /// パスに一致する最初のハンドラーを探す
+    pub fn find<'a>(
+        handlers: &'a [PreviewHandler],
+        path: &Path,
+        kind: EntryKind,
+    ) -> Option<&'a PreviewHandler> {
+        if handlers.is_empty() {
+            return None;
+        }
+        let mime = guess_mime(path, kind);
+        handlers
+            .iter()
+            .find(|handler| handler.matches(path, kind, &mime))
+    }
+
+    fn matches(&self, path: &Path, kind: EntryKind, mime: &str) -> bool {
+        if self.glob.is_none() && self.mime.is_none() && self.kind.is_none() {
+            return false;
+        }
+        let glob_matches = self.glob.as_ref().is_none_or(|glob| glob.is_match(path));
+        let mime_matches = self
+            .mime
+            .as_ref()
+            .is_none_or(|pattern| mime_matches(pattern, mime));
+        let kind_matches = self
+            .kind
+            .as_ref()
+            .is_none_or(|name| EntryKind::from_name(name) == Some(kind));
+        glob_matches && mime_matches && kind_matches
+    }
+
+    /// パスを埋め込んだシェルのコマンドを組み立てる
+    fn shell_command(&self, path: &Path) -> String {
+        let quoted = shell_quote(&path.to_string_lossy());
+        if self.command.contains("{}") {
+            self.command
+                .replace("'{}'", "{}")
+                .replace("\"{}\"", "{}")
+                .replace("{}", &quoted)
+        } else {
+            format!("{} {}", self.command, quoted)
+        }
+    }
+}
+
+/// `image/*`のようなワイルドカードを含むMIMEタイプのパターンに一致するかどうか
+fn mime_matches(pattern: &str, mime: &str) -> bool {
+    match pattern.strip_suffix("/*") {
+        Some(top_level) => mime
+            .split_once('/')
+            .is_some_and(|(mime_top_level, _)| mime_top_level.eq_ignore_ascii_case(top_level)),
+        None => pattern.eq_ignore_ascii_case(mime),
+    }
+}
+
+/// 拡張子からMIMEタイプを推測する
+///
+/// 拡張子が不明なファイルは、内容がテキストなら`text/plain`、そうでなければ`application/octet-stream`とする
+pub fn guess_mime(path: &Path, kind: EntryKind) -> String {
+    if matches!(kind, EntryKind::DIRECTORY | EntryKind::REPOSITORY) {
+        return "inode/directory".to_string();
+    }
+    let extension = path
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let mime = match extension.as_str() {
+        "json" => "application/json",
+        "pdf" => "application/pdf",
+        "zip" => "application/zip",
+        "gz" | "tgz" => "application/gzip",
+        "tar" => "application/x-tar",
+        "xml" => "application/xml",
+        "toml" => "application/toml",
+        "yaml" | "yml" => "application/yaml",
+        "wasm" => "application/wasm",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "bmp" => "image/bmp",
+        "ico" => "image/vnd.microsoft.icon",
+        "tif" | "tiff" => "image/tiff",
+        "svg" => "image/svg+xml",
+        "heic" => "image/heic",
+        "mp3" => "audio/mpeg",
+        "wav" => "audio/wav",
+        "flac" => "audio/flac",
+        "ogg" => "audio/ogg",
+        "mp4" => "video/mp4",
+        "mkv" => "video/x-matroska",
+        "webm" => "video/webm",
+        "mov" => "video/quicktime",
+        "txt" => "text/plain",
+        "md" | "markdown" => "text/markdown",
+        "html" | "htm" => "text/html",
+        "css" => "text/css",
+        "csv" => "text/csv",
+        _ => {
+            return if is_text_file(path) {
+                "text/plain".to_string()
+            } else {
+                "application/octet-stream".to_string()
+            }
+        }
+    };
+    mime.to_string()
+}
+
+fn is_text_file(path: &Path) -> bool {
+    let mut buffer = Vec::new();
+    File::open(path)
+        .and_then(|file| file.take(1024).read_to_end(&mut buffer))
+        .is_ok_and(|_| content_inspector::inspect(&buffer).is_text())
+}
+
+/// シェルの単一引用符で囲む
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+/// 設定した外部コマンドの出力をプレビューとして表示する
+pub struct ExternalEntry {
+    path: PathBuf,
+    handler: PreviewHandler,
+    display_lines: usize,
+}
+
+impl ExternalEntry {
+    pub fn new(path: PathBuf, handler: PreviewHandler, display_lines: usize) -> Self {
+        Self {
+            path,
+            handler,
+            display_lines,
+        }
+    }
+
+    /// コマンドを実行してANSIエスケープシーケンスを含む出力を変換する
+    ///
+    /// コマンドが起動できない、タイムアウトした、または失敗して何も出力しなかった場合はエラーを返す
+    pub fn run(&self) -> io::Result<Vec<Line<'static>>> {
+        let command = self.handler.shell_command(&self.path);
+        let mut shell = Command::new("sh");
+        shell
+            .arg("-c")
+            .arg(&command)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null());
+        // タイムアウトした時にコマンドが起動した子プロセスもまとめて終了できるようにする
+        #[cfg(unix)]
+        std::os::unix::process::CommandExt::process_group(&mut shell, 0);
+        let mut child = shell.spawn()?;
+
+        // 出力がパイプのバッファを超えてもブロックしないように別スレッドで読み込む
+        let stdout = child.stdout.take().expect("stdout is piped");
+        let reader = thread::spawn(move || {
+            let mut output = Vec::new();
+            stdout
+                .take(MAX_OUTPUT_BYTES)
+                .read_to_end(&mut output)
+                .map(|_| output)
+        });
+
+        let deadline = Instant::now() + Duration::from_millis(self.handler.timeout_ms);
+        let status = loop {
+            if let Some(status) = child.try_wait()? {
+                break status;
+            }
+            if Instant::now() >= deadline {
+                kill_process_group(&mut child);
+                let _ = child.wait();
+                return Err(io::Error::new(
+                    io::ErrorKind::TimedOut,
+                    format!("`{}` timed out", command),
+                ));
+            }
+            thread::sleep(POLL_INTERVAL);
+        };
+
+        let output = reader
+            .join()
+            .map_err(|_| io::Error::other("failed to read command output"))??;
+        if !status.success() && output.is_empty() {
+            return Err(io::Error::other(format!(
+                "`{}` failed ({})",
+                command, status
+            )));
+        }
+
+        let text = output
+            .into_text()
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err.to_string()))?;
+        let total = text.lines.len();
+        let mut lines: Vec<Line<'static>> =
+            text.lines.into_iter().take(self.display_lines).collect();
+        if total > self.display_lines {
+            lines.push(Line::from("...and more"));
+        }
+        Ok(lines)
+    }
+}
+
+/// シェルとシェルが起動したプロセスをまとめて終了する
+#[cfg(unix)]
+fn kill_process_group(child: &mut Child) {
+    use nix::sys::signal::{killpg, Signal};
+    use nix::unistd::Pid;
+
+    if killpg(Pid::from_raw(child.id() as i32), Signal::SIGKILL).is_err() {
+        let _ = child.kill();
+    }
+}
+
+#[cfg(not(unix))]
+fn kill_process_group(child: &mut Child) {
+    let _ = child.kill();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helper::lines_to_string;
+    use ratatui::style::Color;
+    use tempfile::tempdir;
+
+    fn handler(command: &str) -> PreviewHandler {
+        PreviewHandler {
+            glob: Some("*.json".parse().unwrap()),
+            mime: None,
+            kind: None,
+            command: command.to_string(),
+            timeout_ms: default_timeout_ms(),
+        }
+    }
+
+    #[test]
+    fn test_find_matches_glob_mime_and_kind() {
+        let handlers = vec![
+            PreviewHandler {
+                glob: None,
+                mime: Some("image/*".to_string()),
+                ..handler("exiftool")
+            },
+            handler("jq -C ."),
+            PreviewHandler {
+                glob: None,
+                kind: Some("repo".to_string()),
+                ..handler("onefetch")
+            },
+        ];
+
+        let find = |path: &str, kind| {
+            PreviewHandler::find(&handlers, Path::new(path), kind)
+                .map(|handler| handler.command.as_str())
+        };
+        assert_eq!(find("/tmp/photo.JPG", EntryKind::FILE), Some("exiftool"));
+        assert_eq!(find("/tmp/data.json", EntryKind::FILE), Some("jq -C ."));
+        assert_eq!(
+            find("/src/project", EntryKind::REPOSITORY),
+            Some("onefetch")
+        );
+        assert_eq!(find("/src/project", EntryKind::DIRECTORY), None);
+        assert_eq!(find("/tmp/notes.md", EntryKind::FILE), None);
+    }
+
+    #[test]
+    fn test_handler_without_conditions_never_matches() {
+        let handlers = vec![PreviewHandler {
+            glob: None,
+            ..handler("cat")
+        }];
+        assert!(PreviewHandler::find(&handlers, Path::new("a.txt"), EntryKind::FILE).is_none());
+    }
+
+    #[test]
+    fn test_mime_matches_wildcard() {
+        assert!(mime_matches("image/*", "image/png"));
+        assert!(mime_matches("application/pdf", "application/pdf"));
+        assert!(!mime_matches("image/*", "video/mp4"));
+    }
+
+    #[test]
+    fn test_guess_mime_falls_back_to_content() {
+        let dir = tempdir().unwrap();
+        let text = dir.path().join("LICENSE");
+        std::fs::write(&text, "MIT License").unwrap();
+        let binary = dir.path().join("blob");
+        std::fs::write(&binary, [0u8, 1, 2, 3]).unwrap();
+
+        assert_eq!(guess_mime(&text, EntryKind::FILE), "text/plain");
+        assert_eq!(
+            guess_mime(&binary, EntryKind::FILE),
+            "application/octet-stream"
+        );
+        assert_eq!(
+            guess_mime(dir.path(), EntryKind::DIRECTORY),
+            "inode/directory"
+        );
+    }
+
+    #[test]
+    fn test_shell_command_quotes_path() {
+        assert_eq!(
+            handler("jq -C . {}").shell_command(Path::new("/tmp/it's.json")),
+            "jq -C . '/tmp/it'\\''s.json'"
+        );
+        assert_eq!(
+            handler("cat").shell_command(Path::new("/tmp/a b.json")),
+            "cat '/tmp/a b.json'"
+        );
+    }
+
+    #[test]
+    fn test_shell_command_ignores_quotes_around_placeholder() {
+        let path = Path::new("/tmp/a b.json");
+        assert_eq!(
+            handler("jq . '{}'").shell_command(path),
+            "jq . '/tmp/a b.json'"
+        );
+        assert_eq!(
+            handler("jq . \"{}\"").shell_command(path),
+            "jq . '/tmp/a b.json'"
+        );
+    }
+
+    #[test]
+    fn test_run_converts_ansi_output() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("data.json");
+        std::fs::write(&path, "").unwrap();
+
+        let entry = ExternalEntry::new(path, handler(r"printf '\033[31mred\033[0m\nplain\n'"), 10);
+        let lines = entry.run().unwrap();
+
+        assert_eq!(lines_to_string(&lines), "red\nplain");
+        assert_eq!(lines[0].spans[0].style.fg, Some(Color::Red));
+    }
+
+    #[test]
+    fn test_run_limits_lines() {
+        let entry = ExternalEntry::new(PathBuf::from("x"), handler("seq 1 20 #"), 3);
+        let lines = lines_to_string(&entry.run().unwrap());
+        assert_eq!(lines, "1\n2\n3\n...and more");
+    }
+
+    #[test]
+    fn test_run_times_out() {
+        let entry = ExternalEntry::new(
+            PathBuf::from("x"),
+            PreviewHandler {
+                timeout_ms: 50,
+                ..handler("sleep 5 #")
+            },
+            10,
+        );
+        let err = entry.run().unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_run_kills_child_processes_on_timeout() {
+        let dir = tempdir().unwrap();
+        let pid_file = dir.path().join("pid");
+        let entry = ExternalEntry::new(
+            PathBuf::from("x"),
+            PreviewHandler {
+                timeout_ms: 200,
+                ..handler(&format!(
+                    "sleep 30 & echo $! > {}; wait #",
+                    pid_file.display()
+                ))
+            },
+            10,
+        );
+
+        assert!(entry.run().is_err());
+
+        let pid = std::fs::read_to_string(&pid_file).unwrap();
+        let stat = Path::new("/proc").join(pid.trim()).join("stat");
+        // 親を失ったプロセスはすぐには回収されないことがあるため、ゾンビも終了とみなす
+        let is_running = || {
+            std::fs::read_to_string(&stat).is_ok_and(|stat| {
+                stat.rsplit(") ")
+                    .next()
+                    .is_some_and(|s| !s.starts_with('Z'))
+            })
+        };
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while is_running() && Instant::now() < deadline {
+            thread::sleep(POLL_INTERVAL);
+        }
+        assert!(!is_running());
+    }
+
+    #[test]
+    fn test_invalid_glob_is_rejected() {
+        assert!("*.{json".parse::<GlobPattern>().is_err());
+        assert_eq!("*.json".parse::<GlobPattern>().unwrap().as_str(), "*.json");
+    }
+
+    #[test]
+    fn test_run_fails_without_output() {
+        let entry = ExternalEntry::new(PathBuf::from("x"), handler("false #"), 10);
+        assert!(entry.run().is_err());
+    }
+}
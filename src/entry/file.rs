@@ -1,15 +1,54 @@
-use bat::{
-    line_range::{LineRange, LineRanges},
-    PrettyPrinter,
+use bat::{Input, PrettyPrinter};
+use ratatui::{
+    style::{Color, Style},
+    text::{Line, Span},
 };
-use ratatui::text::Line;
-use std::{fs::read_to_string, path::PathBuf};
+use std::{
+    fs::File,
+    io::{self, BufRead, BufReader, Read},
+    path::{Path, PathBuf},
+};
+
+use super::{error_line, Entry, EntryKind};
+use crate::config::PreviewConfig;
+
+/// プレビューのために読み込む最大バイト数
+const MAX_PREVIEW_BYTES: u64 = 256 * 1024;
+/// バイナリ判定に使う先頭のバイト数
+const INSPECT_BYTES: usize = 1024;
+/// バイナリファイルのプレビューに表示するバイト数
+const HEXDUMP_BYTES: usize = 256;
+
+/// 拡張子に対応するアイコンがないファイルのアイコン
+const FILE_ICON: &str = "📄";
 
-use super::Entry;
+/// 拡張子ごとのファイルのアイコン
+const FILE_ICONS: &[(&[&str], &str)] = &[
+    (&["zip", "tar", "gz", "tgz", "xz", "bz2", "zst", "7z"], "📦"),
+    (&["rs"], "🦀"),
+    (&["py"], "🐍"),
+    (&["md", "rst", "txt"], "📝"),
+    (&["toml", "json", "yaml", "yml", "ini", "conf"], "🔧"),
+    (&["sh", "bash", "zsh", "fish"], "🐚"),
+    (&["png", "jpg", "jpeg", "gif", "svg", "webp"], "🎨"),
+    (&["pdf"], "📕"),
+];
 
 pub struct FileEntry {
     path: PathBuf,
     display_lines: usize,
+    /// batのテーマ名（`None`の場合はbatのデフォルト）
+    theme: Option<String>,
+    line_numbers: bool,
+}
+
+/// プレビューのために読み込んだファイルの先頭
+struct FileHead {
+    bytes: Vec<u8>,
+    /// 読み込んだ範囲より後ろに続きがあるかどうか
+    truncated: bool,
+    /// ファイル全体のサイズ
+    size: u64,
 }
 
 impl FileEntry {
@@ -17,6 +56,18 @@ impl FileEntry {
         Self {
             path,
             display_lines: display_lines.unwrap_or(10),
+            theme: None,
+            line_numbers: false,
+        }
+    }
+
+    /// プレビューの設定からFileEntryを作成する
+    pub fn from_config(path: PathBuf, config: &PreviewConfig) -> Self {
+        Self {
+            path,
+            display_lines: config.file_lines,
+            theme: config.bat_theme.clone(),
+            line_numbers: config.line_numbers,
         }
     }
 
@@ -27,51 +78,218 @@ impl FileEntry {
     pub fn set_display_lines(&mut self, lines: usize) {
         self.display_lines = lines;
     }
-}
 
-impl Entry for FileEntry {
-    fn get_preview(&self) -> Vec<Line<'static>> {
+    /// ファイルの先頭を読み込む
+    ///
+    /// テキストの場合は表示する行数分だけ、バイナリの場合はヘックスダンプに使う分だけを読み込み、
+    /// どちらの場合も`MAX_PREVIEW_BYTES`を超えては読み込まない
+    fn read_head(&self) -> io::Result<(FileHead, bool)> {
+        let file = File::open(&self.path)?;
+        let size = file.metadata()?.len();
+        let mut reader = BufReader::new(file.take(MAX_PREVIEW_BYTES));
+
+        let buffer = reader.fill_buf()?;
+        let inspected = &buffer[..buffer.len().min(INSPECT_BYTES)];
+        let is_binary = content_inspector::inspect(inspected).is_binary();
+
+        let mut bytes = Vec::new();
+        if is_binary {
+            let limit = HEXDUMP_BYTES.min(buffer.len());
+            bytes.extend_from_slice(&buffer[..limit]);
+            reader.consume(limit);
+        } else {
+            for _ in 0..self.display_lines {
+                if reader.read_until(b'\n', &mut bytes)? == 0 {
+                    break;
+                }
+            }
+        }
+        let truncated = !reader.fill_buf()?.is_empty() || size > MAX_PREVIEW_BYTES;
+        Ok((
+            FileHead {
+                bytes,
+                truncated,
+                size,
+            },
+            is_binary,
+        ))
+    }
+
+    /// テキストファイルをbatでハイライトして描画する
+    fn text_preview(&self, head: &FileHead) -> Vec<Line<'static>> {
         use ansi_to_tui::IntoText;
 
         let mut printer = PrettyPrinter::new();
         let mut writer = String::new();
-        printer
-            .input_file(&self.path)
-            .line_ranges(LineRanges::from(vec![LineRange::new(
-                1,
-                self.display_lines,
-            )]))
-            .print_with_writer(Some(&mut writer))
-            .unwrap();
-
-        // ファイルの行数をカウント
-        if let Ok(content) = read_to_string(&self.path) {
-            let line_count = content.lines().count();
-            if line_count > self.display_lines {
-                writer.push_str("\n...and more");
-            }
+        if let Some(theme) = &self.theme {
+            printer.theme(theme);
         }
+        let printed = printer
+            .input(Input::from_bytes(&head.bytes).name(&self.path))
+            .line_numbers(self.line_numbers)
+            .print_with_writer(Some(&mut writer));
 
-        // ANSIエスケープシーケンスをratatuiのLineに変換
-        writer
-            .lines()
-            .map(|line| {
-                let parsed = line.into_text();
-                match parsed {
-                    Ok(text) => {
-                        if text.lines.is_empty() {
-                            Line::from("")
-                        } else {
-                            text.lines.into_iter().next().unwrap_or_default()
+        let mut lines: Vec<Line<'static>> = match printed {
+            // ANSIエスケープシーケンスをratatuiのLineに変換
+            Ok(_) => writer
+                .lines()
+                .map(|line| {
+                    let parsed = line.into_text();
+                    match parsed {
+                        Ok(text) => {
+                            if text.lines.is_empty() {
+                                Line::from("")
+                            } else {
+                                text.lines.into_iter().next().unwrap_or_default()
+                            }
                         }
+                        Err(_) => Line::from(line.to_string()),
                     }
-                    Err(_) => Line::from(line.to_string()),
-                }
-            })
-            .collect()
+                })
+                .collect(),
+            // ハイライトできない場合はそのまま表示する
+            Err(_) => String::from_utf8_lossy(&head.bytes)
+                .lines()
+                .map(|line| Line::from(line.to_string()))
+                .collect(),
+        };
+
+        if head.truncated {
+            lines.push(Line::from("...and more"));
+        }
+        lines
     }
 }
 
+impl Entry for FileEntry {
+    fn get_preview(&self) -> Vec<Line<'static>> {
+        match self.read_head() {
+            Ok((head, true)) => binary_preview(&head),
+            Ok((head, false)) => self.text_preview(&head),
+            Err(err) => vec![error_line(format!("Cannot read file: {}", err))],
+        }
+    }
+
+    fn kind(&self) -> EntryKind {
+        EntryKind::FILE
+    }
+
+    fn icon(&self) -> &'static str {
+        file_icon(&self.path)
+    }
+}
+
+/// batに組み込まれたテーマの名前かどうか
+pub fn is_bat_theme(name: &str) -> bool {
+    PrettyPrinter::new().themes().any(|theme| theme == name)
+}
+
+/// 拡張子に応じたファイルのアイコンを取得する
+pub fn file_icon(path: &Path) -> &'static str {
+    let extension = path
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_lowercase());
+    extension
+        .and_then(|extension| {
+            FILE_ICONS
+                .iter()
+                .find(|(extensions, _)| extensions.contains(&extension.as_str()))
+        })
+        .map_or(FILE_ICON, |(_, icon)| icon)
+}
+
+/// バイナリファイルの種類・サイズと先頭のヘックスダンプを描画する
+fn binary_preview(head: &FileHead) -> Vec<Line<'static>> {
+    let label = Style::default().fg(Color::Blue);
+    let mut lines = vec![
+        Line::from(vec![
+            Span::styled("Binary file", label),
+            Span::raw(": "),
+            Span::styled(
+                describe_binary(&head.bytes),
+                Style::default().fg(Color::Yellow),
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled("Size", label),
+            Span::raw(": "),
+            Span::raw(format!("{} ({} bytes)", format_size(head.size), head.size)),
+        ]),
+        Line::from(""),
+    ];
+    lines.extend(hexdump(&head.bytes));
+    if head.truncated {
+        lines.push(Line::from("..."));
+    }
+    lines
+}
+
+/// 先頭のマジックナンバーからバイナリファイルの種類を推測する
+fn describe_binary(bytes: &[u8]) -> &'static str {
+    const SIGNATURES: [(&[u8], &str); 12] = [
+        (b"\x89PNG\r\n\x1a\n", "PNG image"),
+        (b"\xff\xd8\xff", "JPEG image"),
+        (b"GIF8", "GIF image"),
+        (b"%PDF", "PDF document"),
+        (b"PK\x03\x04", "Zip archive"),
+        (b"\x1f\x8b", "gzip compressed data"),
+        (b"\x7fELF", "ELF executable"),
+        (b"\xcf\xfa\xed\xfe", "Mach-O executable"),
+        (b"MZ", "Windows executable"),
+        (b"\0asm", "WebAssembly module"),
+        (b"SQLite format 3\0", "SQLite database"),
+        (b"\xfd7zXZ\0", "xz compressed data"),
+    ];
+    SIGNATURES
+        .iter()
+        .find(|(magic, _)| bytes.starts_with(magic))
+        .map_or("binary data", |(_, name)| name)
+}
+
+/// 16バイトごとにオフセット・16進数・ASCIIを並べる
+fn hexdump(bytes: &[u8]) -> Vec<Line<'static>> {
+    bytes
+        .chunks(16)
+        .enumerate()
+        .map(|(row, chunk)| {
+            let hex: Vec<String> = chunk.iter().map(|b| format!("{:02x}", b)).collect();
+            let ascii: String = chunk
+                .iter()
+                .map(|&b| {
+                    if b.is_ascii_graphic() || b == b' ' {
+                        b as char
+                    } else {
+                        '.'
+                    }
+                })
+                .collect();
+            Line::from(vec![
+                Span::styled(
+                    format!("{:08x}  ", row * 16),
+                    Style::default().fg(Color::DarkGray),
+                ),
+                Span::raw(format!("{:<48}", hex.join(" "))),
+                Span::styled(format!(" |{}|", ascii), Style::default().fg(Color::Cyan)),
+            ])
+        })
+        .collect()
+}
+
+/// バイト数を「1.5 KiB」のような表記にする
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["KiB", "MiB", "GiB", "TiB", "PiB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
 #[cfg(test)]
 mod tests {
     use std::fs::File;
@@ -111,4 +329,115 @@ mod tests {
         assert!(preview_str.contains("Line 5"));
         assert!(!preview_str.contains("Line 6"));
     }
+
+    #[test]
+    fn test_from_config_line_numbers() {
+        let temp_dir = tempdir().unwrap();
+        let file_path = temp_dir.path().join("test.txt");
+        let mut file = File::create(&file_path).unwrap();
+        let test_data = (1..=5).map(|i| format!("Line {}\n", i)).collect::<String>();
+        file.write_all(test_data.as_bytes()).unwrap();
+        let config = PreviewConfig {
+            file_lines: 3,
+            line_numbers: true,
+            ..PreviewConfig::default()
+        };
+        let entry = FileEntry::from_config(file_path, &config);
+        let preview = entry.get_preview();
+        let preview_str = lines_to_string(&preview);
+        assert_eq!(entry.get_display_lines(), 3);
+        assert_eq!(preview_str.lines().next(), Some("   1 Line 1"));
+        assert!(!preview_str.contains("Line 4"));
+
+        let without_numbers = FileEntry::from_config(
+            entry.path.clone(),
+            &PreviewConfig {
+                line_numbers: false,
+                ..config
+            },
+        );
+        let preview_str = lines_to_string(&without_numbers.get_preview());
+        assert_eq!(preview_str.lines().next(), Some("Line 1"));
+    }
+
+    #[test]
+    fn test_from_config_bat_theme() {
+        let temp_dir = tempdir().unwrap();
+        let file_path = temp_dir.path().join("test.txt");
+        let mut file = File::create(&file_path).unwrap();
+        file.write_all(b"Hello, theme!").unwrap();
+        let config = PreviewConfig {
+            bat_theme: Some("GitHub".to_string()),
+            ..PreviewConfig::default()
+        };
+        let entry = FileEntry::from_config(file_path, &config);
+        let preview = entry.get_preview();
+        let preview_str = lines_to_string(&preview);
+        assert!(preview_str.contains("Hello, theme!"));
+    }
+
+    #[test]
+    fn test_is_bat_theme() {
+        assert!(is_bat_theme("GitHub"));
+        assert!(is_bat_theme("Nord"));
+        assert!(!is_bat_theme("NoSuchTheme"));
+    }
+
+    #[test]
+    fn test_get_preview_binary_file() {
+        let temp_dir = tempdir().unwrap();
+        let file_path = temp_dir.path().join("image.png");
+        let mut data = b"\x89PNG\r\n\x1a\n".to_vec();
+        data.extend([0u8; 600]);
+        std::fs::write(&file_path, &data).unwrap();
+        let entry = FileEntry::new(file_path, None);
+        let preview_str = lines_to_string(&entry.get_preview());
+        assert!(preview_str.contains("Binary file: PNG image"));
+        assert!(preview_str.contains("Size: 608 B (608 bytes)"));
+        assert!(preview_str.contains("00000000  89 50 4e 47 0d 0a 1a 0a"));
+        assert!(preview_str.contains("|.PNG............|"));
+        assert!(preview_str.contains("000000f0"));
+        assert!(!preview_str.contains("00000100"));
+    }
+
+    #[test]
+    fn test_get_preview_huge_single_line_file_is_bounded() {
+        let temp_dir = tempdir().unwrap();
+        let file_path = temp_dir.path().join("minified.js");
+        std::fs::write(&file_path, "a".repeat(2 * MAX_PREVIEW_BYTES as usize)).unwrap();
+        let entry = FileEntry::new(file_path, None);
+        let preview = entry.get_preview();
+        let preview_str = lines_to_string(&preview);
+        assert!(preview_str.len() <= MAX_PREVIEW_BYTES as usize + 100);
+        assert!(preview_str.ends_with("...and more"));
+    }
+
+    #[test]
+    fn test_get_preview_only_reads_display_lines() {
+        let temp_dir = tempdir().unwrap();
+        let file_path = temp_dir.path().join("log.txt");
+        let content = (1..=10_000)
+            .map(|i| format!("Line {}\n", i))
+            .collect::<String>();
+        std::fs::write(&file_path, content).unwrap();
+        let entry = FileEntry::new(file_path, Some(3));
+        let preview_str = lines_to_string(&entry.get_preview());
+        assert_eq!(preview_str, "Line 1\nLine 2\nLine 3\n...and more");
+    }
+
+    #[test]
+    fn test_get_preview_unreadable_file_shows_error() {
+        let temp_dir = tempdir().unwrap();
+        let entry = FileEntry::new(temp_dir.path().join("missing.txt"), None);
+        let preview_str = lines_to_string(&entry.get_preview());
+        assert!(preview_str.contains("Cannot read file:"));
+    }
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(0), "0 B");
+        assert_eq!(format_size(1023), "1023 B");
+        assert_eq!(format_size(1536), "1.5 KiB");
+        assert_eq!(format_size(5 * 1024 * 1024 * 1024), "5.0 GiB");
+    }
 }
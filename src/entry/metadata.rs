@@ -0,0 +1,530 @@
+use chrono::{DateTime, Local, Utc};
+use lru::LruCache;
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+};
+use std::collections::HashMap;
+use std::fs::{self, Metadata};
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Mutex, OnceLock};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+use super::file::format_size;
+use crate::config::ConfigPath;
+use crate::time_display;
+
+/// ディレクトリのサイズを計算する時に辿る最大のエントリ数
+const MAX_SIZE_ENTRIES: usize = 200_000;
+
+/// 計算したディレクトリのサイズを再計算せずに使い続ける時間
+const SIZE_TTL: Duration = Duration::from_secs(60);
+
+/// サイズをキャッシュするディレクトリの最大数
+const SIZE_CACHE_CAPACITY: usize = 256;
+
+/// ディレクトリの合計サイズ
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DirSize {
+    pub bytes: u64,
+    /// 全てのエントリを数えたかどうか（上限に達した場合は`false`）
+    pub complete: bool,
+}
+
+/// キャッシュしたディレクトリのサイズ
+struct CachedSize {
+    /// 最後に計算を始めた時点のディレクトリの更新日時
+    modified: Option<SystemTime>,
+    /// 最後に計算を始めた時刻
+    requested_at: Instant,
+    /// 最後に計算したサイズ（一度も計算が終わっていない場合は`None`）
+    size: Option<DirSize>,
+    /// 計算中かどうか
+    calculating: bool,
+}
+
+impl CachedSize {
+    /// 計算が終わっていて、最新のサイズがあるかどうか
+    fn is_ready(&self) -> bool {
+        self.size.is_some() && !self.calculating
+    }
+
+    /// 再計算が必要かどうか
+    fn is_stale(&self, modified: Option<SystemTime>) -> bool {
+        self.modified != modified || self.requested_at.elapsed() >= SIZE_TTL
+    }
+}
+
+/// ディレクトリごとのサイズのキャッシュ
+///
+/// 計算したサイズは、ディレクトリの更新日時が変わるか`SIZE_TTL`が過ぎると再計算する。
+/// 再計算中は前回のサイズを使い、最近使われていないディレクトリから破棄する
+fn dir_sizes() -> &'static Mutex<LruCache<PathBuf, CachedSize>> {
+    static DIR_SIZES: OnceLock<Mutex<LruCache<PathBuf, CachedSize>>> = OnceLock::new();
+    DIR_SIZES.get_or_init(|| {
+        Mutex::new(LruCache::new(
+            NonZeroUsize::new(SIZE_CACHE_CAPACITY).unwrap(),
+        ))
+    })
+}
+
+/// ディレクトリのサイズを計算するワーカースレッドへの送信側
+///
+/// 計算は1つのスレッドで順に行い、最後に要求されたディレクトリ（表示中のプレビュー）を先に計算する
+fn size_worker() -> &'static Sender<PathBuf> {
+    static WORKER: OnceLock<Sender<PathBuf>> = OnceLock::new();
+    WORKER.get_or_init(|| {
+        let (request_tx, request_rx) = mpsc::channel::<PathBuf>();
+        thread::spawn(move || {
+            let mut queue = Vec::new();
+            loop {
+                queue.extend(request_rx.try_iter());
+                let Some(path) = queue.pop().or_else(|| request_rx.recv().ok()) else {
+                    break;
+                };
+                let size = calculate_dir_size(&path, MAX_SIZE_ENTRIES);
+                if let Some(cached) = dir_sizes()
+                    .lock()
+                    .unwrap_or_else(|err| err.into_inner())
+                    .peek_mut(&path)
+                {
+                    cached.size = Some(size);
+                    cached.calculating = false;
+                }
+            }
+        });
+        request_tx
+    })
+}
+
+/// ディレクトリの合計サイズを取得する
+///
+/// 未計算の場合はバックグラウンドで計算を始めて`None`を返す。
+/// 計算後にディレクトリが更新された場合や`SIZE_TTL`が過ぎた場合は、再計算を始めて前回のサイズを返す
+pub fn dir_size(path: &Path) -> Option<DirSize> {
+    let modified = fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok();
+    let mut sizes = dir_sizes().lock().unwrap_or_else(|err| err.into_inner());
+    let previous = match sizes.get_mut(path) {
+        Some(cached) if cached.calculating || !cached.is_stale(modified) => return cached.size,
+        Some(cached) => {
+            cached.modified = modified;
+            cached.requested_at = Instant::now();
+            cached.calculating = true;
+            cached.size
+        }
+        None => {
+            sizes.put(
+                path.to_path_buf(),
+                CachedSize {
+                    modified,
+                    requested_at: Instant::now(),
+                    size: None,
+                    calculating: true,
+                },
+            );
+            None
+        }
+    };
+    let _ = size_worker().send(path.to_path_buf());
+    previous
+}
+
+/// ディレクトリのサイズの計算が終わっているかどうか（計算を始めずに確認する）
+///
+/// 計算中や前回のサイズを表示したプレビューを、計算が終わった時に再生成するために使う
+pub fn is_dir_size_ready(path: &Path) -> bool {
+    dir_sizes()
+        .lock()
+        .unwrap_or_else(|err| err.into_inner())
+        .peek(path)
+        .is_some_and(CachedSize::is_ready)
+}
+
+/// ディレクトリ以下のファイルサイズを合計する（シンボリックリンクは辿らない）
+fn calculate_dir_size(path: &Path, max_entries: usize) -> DirSize {
+    let mut bytes = 0;
+    let mut visited = 0;
+    let mut stack = vec![path.to_path_buf()];
+    while let Some(dir) = stack.pop() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            if visited >= max_entries {
+                return DirSize {
+                    bytes,
+                    complete: false,
+                };
+            }
+            visited += 1;
+            let Ok(metadata) = fs::symlink_metadata(entry.path()) else {
+                continue;
+            };
+            if metadata.is_dir() {
+                stack.push(entry.path());
+            } else {
+                bytes += metadata.len();
+            }
+        }
+    }
+    DirSize {
+        bytes,
+        complete: true,
+    }
+}
+
+/// プレビューの先頭に表示する、パスのメタデータとブックマークの統計
+///
+/// # Arguments
+/// * `path` - 対象のパス
+/// * `kind` - エントリの種類の表示名
+/// * `bookmark` - ブックマークの設定（ブックマークされていないパスの場合は`None`）
+pub fn metadata_header(
+    path: &Path,
+    kind: &str,
+    bookmark: Option<&ConfigPath>,
+) -> Vec<Line<'static>> {
+    let label = Style::default().fg(Color::Blue);
+    let separator = || Span::styled(" · ", Style::default().fg(Color::DarkGray));
+    let absolute = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let mut lines = vec![Line::from(Span::styled(
+        absolute.display().to_string(),
+        Style::default()
+            .fg(Color::Cyan)
+            .add_modifier(Modifier::BOLD),
+    ))];
+
+    if let Ok(metadata) = fs::metadata(path) {
+        let mut spans = vec![Span::styled("Kind: ", label), Span::raw(kind.to_string())];
+        spans.push(separator());
+        spans.push(Span::styled("Size: ", label));
+        spans.push(Span::raw(format_entry_size(path, &metadata)));
+        if let Ok(modified) = metadata.modified() {
+            let modified: DateTime<Utc> = modified.into();
+            spans.push(separator());
+            spans.push(Span::styled("Modified: ", label));
+            spans.push(Span::raw(format!(
+                "{} ({})",
+                modified.with_timezone(&Local).format("%Y-%m-%d %H:%M"),
+                time_display::format_relative(modified, Utc::now())
+            )));
+        }
+        lines.push(Line::from(spans));
+        lines.push(Line::from(vec![
+            Span::styled("Permissions: ", label),
+            Span::raw(format_permissions(&metadata)),
+            separator(),
+            Span::styled("Owner: ", label),
+            Span::raw(format_owner(&metadata)),
+        ]));
+    }
+
+    if let Some(bookmark) = bookmark {
+        let tags = if bookmark.tags.is_empty() {
+            "-".to_string()
+        } else {
+            bookmark.tags.join(", ")
+        };
+        lines.push(Line::from(vec![
+            Span::styled("🔖 Tags: ", label),
+            Span::raw(tags),
+            separator(),
+            Span::styled("Persist: ", label),
+            Span::raw(if bookmark.persist { "yes" } else { "no" }),
+            separator(),
+            Span::styled("Opened: ", label),
+            Span::raw(format!("{} times", bookmark.access_count)),
+            separator(),
+            Span::styled("Last used: ", label),
+            Span::raw(time_display::format_relative(
+                bookmark.last_selected,
+                Utc::now(),
+            )),
+        ]));
+    }
+
+    lines.push(Line::styled(
+        "─".repeat(40),
+        Style::default().fg(Color::DarkGray),
+    ));
+    lines
+}
+
+/// ファイルはそのサイズ、ディレクトリは配下の合計サイズを表示する
+fn format_entry_size(path: &Path, metadata: &Metadata) -> String {
+    if !metadata.is_dir() {
+        return format_size(metadata.len());
+    }
+    match dir_size(path) {
+        Some(DirSize {
+            bytes,
+            complete: true,
+        }) => format_size(bytes),
+        Some(DirSize {
+            bytes,
+            complete: false,
+        }) => format!("> {}", format_size(bytes)),
+        None => "calculating...".to_string(),
+    }
+}
+
+/// `drwxr-xr-x`のような形式でパーミッションを表示する
+#[cfg(unix)]
+fn format_permissions(metadata: &Metadata) -> String {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mode = metadata.permissions().mode();
+    let kind = if metadata.is_dir() { 'd' } else { '-' };
+    let bits: String = [
+        (0o400, 'r'),
+        (0o200, 'w'),
+        (0o100, 'x'),
+        (0o040, 'r'),
+        (0o020, 'w'),
+        (0o010, 'x'),
+        (0o004, 'r'),
+        (0o002, 'w'),
+        (0o001, 'x'),
+    ]
+    .iter()
+    .map(|&(bit, c)| if mode & bit != 0 { c } else { '-' })
+    .collect();
+    format!("{}{}", kind, bits)
+}
+
+#[cfg(not(unix))]
+fn format_permissions(metadata: &Metadata) -> String {
+    if metadata.permissions().readonly() {
+        "read-only".to_string()
+    } else {
+        "read-write".to_string()
+    }
+}
+
+/// 所有者を`user:group`の形式で表示する（名前が引けない場合はIDを表示する）
+#[cfg(unix)]
+fn format_owner(metadata: &Metadata) -> String {
+    use std::os::unix::fs::MetadataExt;
+
+    let user = lookup_name(OwnerId::User(metadata.uid()));
+    let group = lookup_name(OwnerId::Group(metadata.gid()));
+    format!("{}:{}", user, group)
+}
+
+#[cfg(not(unix))]
+fn format_owner(_metadata: &Metadata) -> String {
+    "-".to_string()
+}
+
+/// 名前を引くユーザーまたはグループのID
+#[cfg(unix)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum OwnerId {
+    User(u32),
+    Group(u32),
+}
+
+/// IDに対応するユーザー名・グループ名を引く（名前が引けない場合はIDを返す）
+///
+/// NSSを経由して引くためLDAPなどのユーザーにも対応する。
+/// プレビューのたびに問い合わせないよう、結果はIDごとにキャッシュする
+#[cfg(unix)]
+fn lookup_name(id: OwnerId) -> String {
+    use nix::unistd::{Gid, Group, Uid, User};
+
+    static NAMES: OnceLock<Mutex<HashMap<OwnerId, String>>> = OnceLock::new();
+    let mut names = NAMES
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|err| err.into_inner());
+    names
+        .entry(id)
+        .or_insert_with(|| {
+            let (name, raw) = match id {
+                OwnerId::User(uid) => (
+                    User::from_uid(Uid::from_raw(uid))
+                        .ok()
+                        .flatten()
+                        .map(|user| user.name),
+                    uid,
+                ),
+                OwnerId::Group(gid) => (
+                    Group::from_gid(Gid::from_raw(gid))
+                        .ok()
+                        .flatten()
+                        .map(|group| group.name),
+                    gid,
+                ),
+            };
+            name.unwrap_or_else(|| raw.to_string())
+        })
+        .clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helper::lines_to_string;
+    use std::time::{Duration, Instant};
+    use tempfile::tempdir;
+
+    fn bookmark(path: &Path) -> ConfigPath {
+        ConfigPath {
+            path: path.to_path_buf(),
+            last_selected: Utc::now() - chrono::Duration::days(3),
+            persist: true,
+            tags: vec!["work".to_string(), "rust".to_string()],
+            access_count: 7,
+        }
+    }
+
+    #[test]
+    fn test_metadata_header_shows_file_metadata() {
+        let dir = tempdir().unwrap();
+        let file = dir.path().join("file.txt");
+        fs::write(&file, "hello").unwrap();
+
+        let header = lines_to_string(&metadata_header(&file, "file", None));
+
+        assert!(header.contains(&file.canonicalize().unwrap().display().to_string()));
+        assert!(header.contains("Kind: file"));
+        assert!(header.contains("Size: 5 B"));
+        assert!(header.contains("(just now)"));
+        assert!(header.contains("Permissions: "));
+        assert!(header.contains("Owner: "));
+        assert!(!header.contains("Tags:"));
+    }
+
+    #[test]
+    fn test_metadata_header_shows_bookmark_stats() {
+        let dir = tempdir().unwrap();
+
+        let header = lines_to_string(&metadata_header(
+            dir.path(),
+            "directory",
+            Some(&bookmark(dir.path())),
+        ));
+
+        assert!(header.contains("Tags: work, rust"));
+        assert!(header.contains("Persist: yes"));
+        assert!(header.contains("Opened: 7 times"));
+        assert!(header.contains("Last used: 3 days ago"));
+    }
+
+    #[test]
+    fn test_metadata_header_of_missing_path_shows_only_bookmark() {
+        let dir = tempdir().unwrap();
+        let missing = dir.path().join("gone");
+
+        let header = lines_to_string(&metadata_header(
+            &missing,
+            "directory",
+            Some(&bookmark(&missing)),
+        ));
+
+        assert!(!header.contains("Kind:"));
+        assert!(header.contains("Tags: work, rust"));
+    }
+
+    #[test]
+    fn test_dir_size_is_calculated_in_background() {
+        let dir = tempdir().unwrap();
+        fs::create_dir(dir.path().join("sub")).unwrap();
+        fs::write(dir.path().join("a.txt"), "12345").unwrap();
+        fs::write(dir.path().join("sub").join("b.txt"), "123").unwrap();
+
+        let deadline = Instant::now() + Duration::from_secs(10);
+        let size = loop {
+            if let Some(size) = dir_size(dir.path()) {
+                break size;
+            }
+            assert!(Instant::now() < deadline, "size was not calculated");
+            thread::sleep(Duration::from_millis(5));
+        };
+
+        assert_eq!(
+            size,
+            DirSize {
+                bytes: 8,
+                complete: true
+            }
+        );
+    }
+
+    #[test]
+    fn test_dir_size_is_recalculated_when_directory_changes() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("a.txt"), "12345").unwrap();
+        let wait_for_size = || {
+            let deadline = Instant::now() + Duration::from_secs(10);
+            loop {
+                if let Some(size) = dir_size(dir.path()) {
+                    break size;
+                }
+                assert!(Instant::now() < deadline, "size was not calculated");
+                thread::sleep(Duration::from_millis(5));
+            }
+        };
+        assert_eq!(wait_for_size().bytes, 5);
+        assert!(is_dir_size_ready(dir.path()));
+
+        fs::write(dir.path().join("b.txt"), "123").unwrap();
+        fs::File::open(dir.path())
+            .unwrap()
+            .set_modified(SystemTime::now() + Duration::from_secs(60))
+            .unwrap();
+
+        // 再計算中は前回のサイズを返す
+        assert_eq!(dir_size(dir.path()).unwrap().bytes, 5);
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while !is_dir_size_ready(dir.path()) {
+            assert!(Instant::now() < deadline, "size was not recalculated");
+            thread::sleep(Duration::from_millis(5));
+        }
+        assert_eq!(dir_size(dir.path()).unwrap().bytes, 8);
+    }
+
+    #[test]
+    fn test_calculate_dir_size_stops_at_max_entries() {
+        let dir = tempdir().unwrap();
+        for i in 0..5 {
+            fs::write(dir.path().join(format!("{}.txt", i)), "1").unwrap();
+        }
+
+        let size = calculate_dir_size(dir.path(), 3);
+
+        assert_eq!(
+            size,
+            DirSize {
+                bytes: 3,
+                complete: false
+            }
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_format_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempdir().unwrap();
+        let file = dir.path().join("script.sh");
+        fs::write(&file, "").unwrap();
+        fs::set_permissions(&file, fs::Permissions::from_mode(0o754)).unwrap();
+
+        let metadata = fs::metadata(&file).unwrap();
+        assert_eq!(format_permissions(&metadata), "-rwxr-xr--");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_lookup_name_by_id() {
+        assert_eq!(lookup_name(OwnerId::User(0)), "root");
+        assert_eq!(lookup_name(OwnerId::User(4_000_000_000)), "4000000000");
+    }
+}
@@ -0,0 +1,541 @@
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+};
+use regex::Regex;
+use std::fs;
+use std::path::Path;
+
+/// 一覧として表示する項目の最大数
+const MAX_LISTED_ITEMS: usize = 10;
+/// 読み込むマニフェストの最大バイト数
+const MAX_MANIFEST_BYTES: u64 = 256 * 1024;
+
+/// マニフェストから判定するプロジェクトの種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProjectKind {
+    Rust,
+    Node,
+    Go,
+    Python,
+    Java,
+}
+
+impl ProjectKind {
+    const ALL: [Self; 5] = [Self::Rust, Self::Node, Self::Go, Self::Python, Self::Java];
+
+    /// 種類を判定するマニフェストのファイル名
+    pub fn manifest(self) -> &'static str {
+        match self {
+            Self::Rust => "Cargo.toml",
+            Self::Node => "package.json",
+            Self::Go => "go.mod",
+            Self::Python => "pyproject.toml",
+            Self::Java => "pom.xml",
+        }
+    }
+
+    /// 一覧に表示する言語のバッジ
+    pub fn badge(self) -> &'static str {
+        match self {
+            Self::Rust => "rust",
+            Self::Node => "node",
+            Self::Go => "go",
+            Self::Python => "python",
+            Self::Java => "java",
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Rust => "Rust",
+            Self::Node => "Node.js",
+            Self::Go => "Go",
+            Self::Python => "Python",
+            Self::Java => "Java (Maven)",
+        }
+    }
+
+    /// ディレクトリにあるマニフェストからプロジェクトの種類を判定する
+    pub fn detect(dir: &Path) -> Vec<Self> {
+        Self::ALL
+            .into_iter()
+            .filter(|kind| dir.join(kind.manifest()).is_file())
+            .collect()
+    }
+}
+
+/// 一覧に表示するプロジェクトの言語のバッジ
+pub fn project_badges(dir: &Path) -> Vec<String> {
+    ProjectKind::detect(dir)
+        .into_iter()
+        .map(|kind| kind.badge().to_string())
+        .collect()
+}
+
+/// マニフェストから読み取ったプロジェクトの概要
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProjectSummary {
+    pub name: Option<String>,
+    pub version: Option<String>,
+    /// ワークスペースのメンバーやサブモジュール
+    pub members: Vec<String>,
+    /// npmのスクリプトやビルドのターゲット
+    pub scripts: Vec<String>,
+}
+
+impl ProjectSummary {
+    /// マニフェストを読み込んで概要を作成する（読み込めない場合は`None`）
+    pub fn read(dir: &Path, kind: ProjectKind) -> Option<Self> {
+        let content = read_manifest(&dir.join(kind.manifest()))?;
+        match kind {
+            ProjectKind::Rust => Self::from_cargo(dir, &content),
+            ProjectKind::Node => Self::from_package_json(&content),
+            ProjectKind::Go => Some(Self::from_go_mod(dir, &content)),
+            ProjectKind::Python => Self::from_pyproject(&content),
+            ProjectKind::Java => Some(Self::from_pom(&content)),
+        }
+    }
+
+    fn from_cargo(dir: &Path, content: &str) -> Option<Self> {
+        let manifest: toml::Table = toml::from_str(content).ok()?;
+        let package = manifest.get("package").and_then(|value| value.as_table());
+        let name = package
+            .and_then(|package| package.get("name"))
+            .and_then(|value| value.as_str())
+            .map(str::to_string);
+        // `version.workspace = true`のような継承は表示しない
+        let version = package
+            .and_then(|package| package.get("version"))
+            .and_then(|value| value.as_str())
+            .map(str::to_string);
+        let members = manifest
+            .get("workspace")
+            .and_then(|workspace| workspace.get("members"))
+            .map(toml_strings)
+            .unwrap_or_default();
+
+        let mut scripts = Vec::new();
+        if manifest.contains_key("lib") || dir.join("src").join("lib.rs").is_file() {
+            scripts.push("lib".to_string());
+        }
+        if let Some(name) = &name {
+            if dir.join("src").join("main.rs").is_file() {
+                scripts.push(format!("bin {}", name));
+            }
+        }
+        if let Some(bins) = manifest.get("bin").and_then(|value| value.as_array()) {
+            scripts.extend(
+                bins.iter()
+                    .filter_map(|bin| bin.get("name").and_then(|value| value.as_str()))
+                    .map(|bin| format!("bin {}", bin))
+                    .filter(|bin| !scripts.contains(bin))
+                    .collect::<Vec<_>>(),
+            );
+        }
+        Some(Self {
+            name,
+            version,
+            members,
+            scripts,
+        })
+    }
+
+    fn from_package_json(content: &str) -> Option<Self> {
+        let manifest: serde_json::Value = serde_json::from_str(content).ok()?;
+        let string = |key: &str| {
+            manifest
+                .get(key)
+                .and_then(|value| value.as_str())
+                .map(str::to_string)
+        };
+        // `workspaces`は配列か、`packages`を持つオブジェクトのどちらか
+        let members = manifest
+            .get("workspaces")
+            .map(|workspaces| workspaces.get("packages").unwrap_or(workspaces))
+            .and_then(|packages| packages.as_array())
+            .map(|packages| {
+                packages
+                    .iter()
+                    .filter_map(|package| package.as_str().map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let scripts = manifest
+            .get("scripts")
+            .and_then(|scripts| scripts.as_object())
+            .map(|scripts| scripts.keys().cloned().collect())
+            .unwrap_or_default();
+        Some(Self {
+            name: string("name"),
+            version: string("version"),
+            members,
+            scripts,
+        })
+    }
+
+    fn from_go_mod(dir: &Path, content: &str) -> Self {
+        let directive = |name: &str| {
+            content.lines().find_map(|line| {
+                line.trim()
+                    .strip_prefix(name)
+                    .filter(|rest| rest.starts_with(char::is_whitespace))
+                    .map(|rest| rest.trim().to_string())
+            })
+        };
+        let members = read_manifest(&dir.join("go.work"))
+            .map(|work| parse_go_work_uses(&work))
+            .unwrap_or_default();
+        Self {
+            name: directive("module"),
+            version: directive("go").map(|version| format!("go {}", version)),
+            members,
+            scripts: Vec::new(),
+        }
+    }
+
+    fn from_pyproject(content: &str) -> Option<Self> {
+        let manifest: toml::Table = toml::from_str(content).ok()?;
+        // PEP 621の`[project]`を優先し、無ければPoetryの`[tool.poetry]`を使う
+        let project = manifest
+            .get("project")
+            .or_else(|| manifest.get("tool").and_then(|tool| tool.get("poetry")))?;
+        let string = |key: &str| {
+            project
+                .get(key)
+                .and_then(|value| value.as_str())
+                .map(str::to_string)
+        };
+        let scripts = project
+            .get("scripts")
+            .and_then(|scripts| scripts.as_table())
+            .map(|scripts| scripts.keys().cloned().collect())
+            .unwrap_or_default();
+        Some(Self {
+            name: string("name"),
+            version: string("version"),
+            members: Vec::new(),
+            scripts,
+        })
+    }
+
+    fn from_pom(content: &str) -> Self {
+        // 親や依存関係の中の要素を拾わないように、ネストしたブロックを取り除く
+        let nested = Regex::new(
+            r"(?s)<(parent|dependencies|dependencyManagement|build|profiles|reporting|pluginRepositories|repositories)>.*?</(parent|dependencies|dependencyManagement|build|profiles|reporting|pluginRepositories|repositories)>",
+        )
+        .expect("valid regex");
+        let project = nested.replace_all(content, "");
+        let element = |name: &str| {
+            Regex::new(&format!(r"<{0}>\s*([^<]+?)\s*</{0}>", name))
+                .expect("valid regex")
+                .captures(&project)
+                .map(|captures| captures[1].to_string())
+        };
+        let name = match (element("groupId"), element("artifactId")) {
+            (Some(group), Some(artifact)) => Some(format!("{}:{}", group, artifact)),
+            (None, artifact) => artifact,
+            (Some(_), None) => None,
+        };
+        let module = Regex::new(r"<module>\s*([^<]+?)\s*</module>").expect("valid regex");
+        Self {
+            name,
+            version: element("version"),
+            members: module
+                .captures_iter(&project)
+                .map(|captures| captures[1].to_string())
+                .collect(),
+            scripts: Vec::new(),
+        }
+    }
+}
+
+fn read_manifest(path: &Path) -> Option<String> {
+    let metadata = fs::metadata(path).ok()?;
+    if metadata.len() > MAX_MANIFEST_BYTES {
+        return None;
+    }
+    fs::read_to_string(path).ok()
+}
+
+fn toml_strings(value: &toml::Value) -> Vec<String> {
+    value
+        .as_array()
+        .map(|values| {
+            values
+                .iter()
+                .filter_map(|value| value.as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// `go.work`の`use`ディレクティブ（単独とブロックの両方）からモジュールを集める
+fn parse_go_work_uses(content: &str) -> Vec<String> {
+    let mut uses = Vec::new();
+    let mut in_block = false;
+    for line in content.lines() {
+        let line = line.split("//").next().unwrap_or_default().trim();
+        if in_block {
+            if line == ")" {
+                in_block = false;
+            } else if !line.is_empty() {
+                uses.push(line.to_string());
+            }
+        } else if let Some(rest) = line.strip_prefix("use") {
+            let rest = rest.trim();
+            if rest == "(" {
+                in_block = true;
+            } else if !rest.is_empty() {
+                uses.push(rest.to_string());
+            }
+        }
+    }
+    uses
+}
+
+/// ディレクトリで見つかったプロジェクトの概要を描画する
+pub fn render_projects(dir: &Path) -> Vec<Line<'static>> {
+    let mut lines = Vec::new();
+    for kind in ProjectKind::detect(dir) {
+        let Some(summary) = ProjectSummary::read(dir, kind) else {
+            continue;
+        };
+        lines.push(Line::from(vec![
+            Span::raw("🧰 "),
+            Span::styled(
+                format!("{} project", kind.label()),
+                Style::default().fg(Color::Yellow),
+            ),
+            Span::styled(
+                format!(" ({})", kind.manifest()),
+                Style::default().fg(Color::DarkGray),
+            ),
+        ]));
+        if let Some(name) = &summary.name {
+            let mut spans = vec![
+                Span::raw("   Package: "),
+                Span::styled(name.clone(), Style::default().add_modifier(Modifier::BOLD)),
+            ];
+            if let Some(version) = &summary.version {
+                spans.push(Span::styled(
+                    format!(" {}", version),
+                    Style::default().fg(Color::Green),
+                ));
+            }
+            lines.push(Line::from(spans));
+        }
+        let members_label = match kind {
+            ProjectKind::Java => "Modules",
+            _ => "Workspace",
+        };
+        push_list(&mut lines, members_label, &summary.members);
+        let scripts_label = match kind {
+            ProjectKind::Rust => "Targets",
+            _ => "Scripts",
+        };
+        push_list(&mut lines, scripts_label, &summary.scripts);
+        lines.push(Line::from(""));
+    }
+    lines
+}
+
+/// 項目を1行にまとめ、多すぎる場合は件数で省略する
+fn push_list(lines: &mut Vec<Line<'static>>, label: &str, items: &[String]) {
+    if items.is_empty() {
+        return;
+    }
+    let mut listed = items
+        .iter()
+        .take(MAX_LISTED_ITEMS)
+        .cloned()
+        .collect::<Vec<_>>()
+        .join(", ");
+    if items.len() > MAX_LISTED_ITEMS {
+        listed.push_str(&format!(" (+{} more)", items.len() - MAX_LISTED_ITEMS));
+    }
+    lines.push(Line::from(vec![
+        Span::raw(format!("   {}: ", label)),
+        Span::styled(listed, Style::default().fg(Color::Cyan)),
+    ]));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helper::lines_to_string;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_detect_project_kinds_from_manifests() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("Cargo.toml"), "").unwrap();
+        fs::write(dir.path().join("package.json"), "{}").unwrap();
+
+        assert_eq!(
+            ProjectKind::detect(dir.path()),
+            vec![ProjectKind::Rust, ProjectKind::Node]
+        );
+        assert!(ProjectKind::detect(tempdir().unwrap().path()).is_empty());
+    }
+
+    #[test]
+    fn test_cargo_summary_lists_members_and_targets() {
+        let dir = tempdir().unwrap();
+        fs::write(
+            dir.path().join("Cargo.toml"),
+            r#"
+            [package]
+            name = "pavo"
+            version = "0.1.0"
+
+            [workspace]
+            members = ["crates/core", "crates/cli"]
+
+            [[bin]]
+            name = "pavo-helper"
+            path = "src/helper.rs"
+            "#,
+        )
+        .unwrap();
+        fs::create_dir(dir.path().join("src")).unwrap();
+        fs::write(dir.path().join("src").join("main.rs"), "").unwrap();
+
+        let summary = ProjectSummary::read(dir.path(), ProjectKind::Rust).unwrap();
+
+        assert_eq!(summary.name.as_deref(), Some("pavo"));
+        assert_eq!(summary.version.as_deref(), Some("0.1.0"));
+        assert_eq!(summary.members, vec!["crates/core", "crates/cli"]);
+        assert_eq!(summary.scripts, vec!["bin pavo", "bin pavo-helper"]);
+    }
+
+    #[test]
+    fn test_package_json_summary() {
+        let dir = tempdir().unwrap();
+        fs::write(
+            dir.path().join("package.json"),
+            r#"{
+                "name": "web",
+                "version": "2.3.4",
+                "workspaces": { "packages": ["packages/*"] },
+                "scripts": { "build": "vite build", "test": "vitest" }
+            }"#,
+        )
+        .unwrap();
+
+        let summary = ProjectSummary::read(dir.path(), ProjectKind::Node).unwrap();
+
+        assert_eq!(summary.name.as_deref(), Some("web"));
+        assert_eq!(summary.version.as_deref(), Some("2.3.4"));
+        assert_eq!(summary.members, vec!["packages/*"]);
+        assert_eq!(summary.scripts, vec!["build", "test"]);
+    }
+
+    #[test]
+    fn test_go_mod_summary_with_workspace() {
+        let dir = tempdir().unwrap();
+        fs::write(
+            dir.path().join("go.mod"),
+            "module github.com/example/tool\n\ngo 1.22\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("go.work"),
+            "go 1.22\n\nuse (\n\t./api // service\n\t./cli\n)\nuse ./extra\n",
+        )
+        .unwrap();
+
+        let summary = ProjectSummary::read(dir.path(), ProjectKind::Go).unwrap();
+
+        assert_eq!(summary.name.as_deref(), Some("github.com/example/tool"));
+        assert_eq!(summary.version.as_deref(), Some("go 1.22"));
+        assert_eq!(summary.members, vec!["./api", "./cli", "./extra"]);
+    }
+
+    #[test]
+    fn test_pyproject_summary_supports_poetry() {
+        let dir = tempdir().unwrap();
+        fs::write(
+            dir.path().join("pyproject.toml"),
+            r#"
+            [tool.poetry]
+            name = "analysis"
+            version = "0.3.0"
+
+            [tool.poetry.scripts]
+            analyze = "analysis.cli:main"
+            "#,
+        )
+        .unwrap();
+
+        let summary = ProjectSummary::read(dir.path(), ProjectKind::Python).unwrap();
+
+        assert_eq!(summary.name.as_deref(), Some("analysis"));
+        assert_eq!(summary.version.as_deref(), Some("0.3.0"));
+        assert_eq!(summary.scripts, vec!["analyze"]);
+    }
+
+    #[test]
+    fn test_pom_summary_ignores_parent_and_dependencies() {
+        let dir = tempdir().unwrap();
+        fs::write(
+            dir.path().join("pom.xml"),
+            r#"<project>
+                <parent>
+                    <groupId>org.parent</groupId>
+                    <artifactId>parent</artifactId>
+                    <version>9.9.9</version>
+                </parent>
+                <groupId>com.example</groupId>
+                <artifactId>service</artifactId>
+                <version>1.2.0</version>
+                <modules>
+                    <module>api</module>
+                    <module>core</module>
+                </modules>
+                <dependencies>
+                    <dependency>
+                        <artifactId>junit</artifactId>
+                        <version>4.13</version>
+                    </dependency>
+                </dependencies>
+            </project>"#,
+        )
+        .unwrap();
+
+        let summary = ProjectSummary::read(dir.path(), ProjectKind::Java).unwrap();
+
+        assert_eq!(summary.name.as_deref(), Some("com.example:service"));
+        assert_eq!(summary.version.as_deref(), Some("1.2.0"));
+        assert_eq!(summary.members, vec!["api", "core"]);
+    }
+
+    #[test]
+    fn test_render_projects() {
+        let dir = tempdir().unwrap();
+        fs::write(
+            dir.path().join("package.json"),
+            r#"{"name": "web", "version": "1.0.0", "scripts": {"dev": "vite"}}"#,
+        )
+        .unwrap();
+
+        let preview = lines_to_string(&render_projects(dir.path()));
+
+        assert!(preview.contains("🧰 Node.js project (package.json)"));
+        assert!(preview.contains("Package: web 1.0.0"));
+        assert!(preview.contains("Scripts: dev"));
+    }
+
+    #[test]
+    fn test_push_list_omits_excess_items() {
+        let items: Vec<String> = (0..12).map(|i| i.to_string()).collect();
+        let mut lines = Vec::new();
+
+        push_list(&mut lines, "Workspace", &items);
+
+        assert_eq!(
+            lines_to_string(&lines),
+            "   Workspace: 0, 1, 2, 3, 4, 5, 6, 7, 8, 9 (+2 more)"
+        );
+    }
+}
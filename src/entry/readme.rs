@@ -0,0 +1,441 @@
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+};
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// READMEとして扱うファイル名（優先順、大文字小文字は区別しない）
+const README_NAMES: [&str; 5] = [
+    "readme.md",
+    "readme.markdown",
+    "readme.rst",
+    "readme.txt",
+    "readme",
+];
+
+/// READMEから読み込む最大バイト数
+const MAX_README_BYTES: u64 = 64 * 1024;
+
+/// ディレクトリ直下のREADMEを探す
+pub fn find_readme(dir: &Path) -> Option<PathBuf> {
+    let entries: Vec<PathBuf> = fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+        .collect();
+    README_NAMES.iter().find_map(|name| {
+        entries
+            .iter()
+            .find(|path| {
+                path.file_name()
+                    .is_some_and(|file_name| file_name.to_string_lossy().to_lowercase() == *name)
+            })
+            .cloned()
+    })
+}
+
+/// READMEを見出し付きのプレビューとして描画する
+///
+/// # Arguments
+/// * `path` - READMEのパス
+/// * `max_lines` - 表示する最大行数
+pub fn render_readme(path: &Path, max_lines: usize) -> Vec<Line<'static>> {
+    let mut content = String::new();
+    let read = File::open(path).and_then(|file| {
+        let mut bytes = Vec::new();
+        file.take(MAX_README_BYTES).read_to_end(&mut bytes)?;
+        content = String::from_utf8_lossy(&bytes).into_owned();
+        Ok(())
+    });
+    if read.is_err() {
+        return Vec::new();
+    }
+
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let extension = path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let body = match extension.as_str() {
+        "md" | "markdown" => render_markdown(&content),
+        "rst" => render_rst(&content),
+        _ => content
+            .lines()
+            .map(|line| Line::from(line.to_string()))
+            .collect(),
+    };
+
+    let mut lines = vec![
+        Line::from(""),
+        Line::from(vec![
+            Span::raw("📖 "),
+            Span::styled(name, Style::default().fg(Color::Yellow)),
+        ]),
+    ];
+    let truncated = body.len() > max_lines;
+    lines.extend(body.into_iter().take(max_lines));
+    if truncated {
+        lines.push(Line::styled("...", Style::default().fg(Color::DarkGray)));
+    }
+    lines
+}
+
+/// Markdownを見出し・強調・コードブロック・リストのスタイル付きで描画する
+pub fn render_markdown(content: &str) -> Vec<Line<'static>> {
+    let mut lines: Vec<Line<'static>> = Vec::new();
+    let mut in_code_block = false;
+
+    for raw in content.lines() {
+        let trimmed = raw.trim_start();
+
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_code_block = !in_code_block;
+            continue;
+        }
+        if in_code_block {
+            lines.push(Line::from(vec![
+                Span::raw("  "),
+                Span::styled(raw.to_string(), Style::default().fg(Color::Cyan)),
+            ]));
+            continue;
+        }
+
+        // バッジなどのHTMLは表示しない
+        if trimmed.starts_with('<') {
+            continue;
+        }
+        if trimmed.is_empty() {
+            if lines.last().is_some_and(|line| line.width() > 0) {
+                lines.push(Line::from(""));
+            }
+            continue;
+        }
+
+        if let Some((level, text)) = parse_heading(trimmed) {
+            let style = match level {
+                1 => Style::default()
+                    .fg(Color::Magenta)
+                    .add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+                2 => Style::default()
+                    .fg(Color::Magenta)
+                    .add_modifier(Modifier::BOLD),
+                _ => Style::default().add_modifier(Modifier::BOLD),
+            };
+            lines.push(Line::from(render_inline(text, style)));
+        } else if is_horizontal_rule(trimmed) {
+            lines.push(Line::styled(
+                "────────────────",
+                Style::default().fg(Color::DarkGray),
+            ));
+        } else if let Some(text) = trimmed.strip_prefix('>') {
+            let style = Style::default()
+                .fg(Color::DarkGray)
+                .add_modifier(Modifier::ITALIC);
+            let mut spans = vec![Span::styled("│ ", style)];
+            spans.extend(render_inline(text.trim_start(), style));
+            lines.push(Line::from(spans));
+        } else if let Some((marker, text)) = parse_list_item(trimmed) {
+            let indent = " ".repeat(raw.len() - trimmed.len());
+            let mut spans = vec![
+                Span::raw(indent),
+                Span::styled(marker, Style::default().fg(Color::Yellow)),
+            ];
+            spans.extend(render_inline(text, Style::default()));
+            lines.push(Line::from(spans));
+        } else {
+            lines.push(Line::from(render_inline(trimmed, Style::default())));
+        }
+    }
+
+    while lines.last().is_some_and(|line| line.width() == 0) {
+        lines.pop();
+    }
+    lines
+}
+
+/// reStructuredTextの見出し（下線付きの行）を太字にして描画する
+fn render_rst(content: &str) -> Vec<Line<'static>> {
+    let source: Vec<&str> = content.lines().collect();
+    let mut lines = Vec::new();
+    let mut i = 0;
+    while i < source.len() {
+        let line = source[i];
+        let underline = source.get(i + 1).copied().unwrap_or_default();
+        if !line.trim().is_empty() && is_rst_underline(underline, line) {
+            lines.push(Line::styled(
+                line.to_string(),
+                Style::default()
+                    .fg(Color::Magenta)
+                    .add_modifier(Modifier::BOLD),
+            ));
+            i += 2;
+        } else if is_rst_underline(line, "") {
+            // 見出しの上線は表示しない
+            i += 1;
+        } else {
+            lines.push(Line::from(line.to_string()));
+            i += 1;
+        }
+    }
+    lines
+}
+
+fn is_rst_underline(line: &str, title: &str) -> bool {
+    let line = line.trim_end();
+    let Some(first) = line.chars().next() else {
+        return false;
+    };
+    "=-~^*#".contains(first)
+        && line.chars().all(|c| c == first)
+        && line.chars().count() >= title.trim_end().chars().count().max(3)
+}
+
+fn parse_heading(line: &str) -> Option<(usize, &str)> {
+    let level = line.chars().take_while(|&c| c == '#').count();
+    if level == 0 || level > 6 {
+        return None;
+    }
+    let text = &line[level..];
+    if !text.is_empty() && !text.starts_with(' ') {
+        return None;
+    }
+    Some((level, text.trim().trim_end_matches('#').trim_end()))
+}
+
+fn is_horizontal_rule(line: &str) -> bool {
+    let compact: String = line.chars().filter(|c| !c.is_whitespace()).collect();
+    compact.len() >= 3
+        && ['-', '*', '_']
+            .iter()
+            .any(|&c| compact.chars().all(|x| x == c))
+}
+
+fn parse_list_item(line: &str) -> Option<(String, &str)> {
+    for bullet in ["- ", "* ", "+ "] {
+        if let Some(text) = line.strip_prefix(bullet) {
+            return Some(("• ".to_string(), text));
+        }
+    }
+    let digits = line.chars().take_while(char::is_ascii_digit).count();
+    if digits > 0 {
+        if let Some(text) = line[digits..].strip_prefix(". ") {
+            return Some((format!("{}. ", &line[..digits]), text));
+        }
+    }
+    None
+}
+
+/// 行内の強調・コード・リンク・画像を描画する
+fn render_inline(text: &str, base: Style) -> Vec<Span<'static>> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut spans = Vec::new();
+    let mut plain = String::new();
+    let mut i = 0;
+
+    let flush = |plain: &mut String, spans: &mut Vec<Span<'static>>| {
+        if !plain.is_empty() {
+            spans.push(Span::styled(std::mem::take(plain), base));
+        }
+    };
+
+    while i < chars.len() {
+        let rest = &chars[i..];
+        // 単語中の`_`は強調として扱わない（snake_caseなど）
+        let intraword = rest[0] == '_' && i > 0 && chars[i - 1].is_alphanumeric();
+        // `code`
+        if rest[0] == '`' {
+            if let Some(end) = find_closing(rest, 1, &['`']) {
+                flush(&mut plain, &mut spans);
+                let code: String = rest[1..end].iter().collect();
+                spans.push(Span::styled(code, base.fg(Color::Cyan)));
+                i += end + 1;
+                continue;
+            }
+        }
+        // **bold** / __bold__
+        if !intraword && rest.len() > 2 && (rest[..2] == ['*', '*'] || rest[..2] == ['_', '_']) {
+            let marker = [rest[0], rest[1]];
+            if let Some(end) = find_closing(rest, 2, &marker) {
+                flush(&mut plain, &mut spans);
+                let inner: String = rest[2..end].iter().collect();
+                spans.extend(render_inline(&inner, base.add_modifier(Modifier::BOLD)));
+                i += end + 2;
+                continue;
+            }
+        }
+        // *italic* / _italic_
+        if !intraword
+            && (rest[0] == '*' || rest[0] == '_')
+            && rest.get(1).is_some_and(|c| !c.is_whitespace())
+        {
+            if let Some(end) = find_closing(rest, 1, &[rest[0]]) {
+                flush(&mut plain, &mut spans);
+                let inner: String = rest[1..end].iter().collect();
+                spans.extend(render_inline(&inner, base.add_modifier(Modifier::ITALIC)));
+                i += end + 1;
+                continue;
+            }
+        }
+        // [text](url) / ![alt](url)
+        let is_image = rest[0] == '!' && rest.get(1) == Some(&'[');
+        if rest[0] == '[' || is_image {
+            let open = usize::from(is_image);
+            if let Some((label, consumed)) = parse_link(&rest[open..]) {
+                flush(&mut plain, &mut spans);
+                if is_image {
+                    if !label.is_empty() {
+                        spans.push(Span::styled(
+                            format!("[{}]", label),
+                            base.fg(Color::DarkGray),
+                        ));
+                    }
+                } else {
+                    spans.push(Span::styled(
+                        label,
+                        base.fg(Color::Blue).add_modifier(Modifier::UNDERLINED),
+                    ));
+                }
+                i += open + consumed;
+                continue;
+            }
+        }
+        plain.push(rest[0]);
+        i += 1;
+    }
+    flush(&mut plain, &mut spans);
+    spans
+}
+
+/// `start`以降で`marker`が出現する位置を探す（空の強調は対象外）
+fn find_closing(chars: &[char], start: usize, marker: &[char]) -> Option<usize> {
+    (start + 1..=chars.len().checked_sub(marker.len())?)
+        .find(|&i| chars[i..i + marker.len()] == *marker)
+}
+
+/// `[label](url)`を解析し、ラベルと消費した文字数を返す
+fn parse_link(chars: &[char]) -> Option<(String, usize)> {
+    let close = chars.iter().position(|&c| c == ']')?;
+    if chars.get(close + 1) != Some(&'(') {
+        return None;
+    }
+    let end = chars[close + 2..].iter().position(|&c| c == ')')? + close + 2;
+    Some((chars[1..close].iter().collect(), end + 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helper::lines_to_string;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_find_readme_優先順に大文字小文字を区別せず探す() {
+        // Arrange
+        let temp_dir = tempdir().unwrap();
+        fs::write(temp_dir.path().join("readme.txt"), "text").unwrap();
+        fs::write(temp_dir.path().join("README.md"), "markdown").unwrap();
+
+        // Act
+        let readme = find_readme(temp_dir.path());
+
+        // Assert
+        assert_eq!(readme, Some(temp_dir.path().join("README.md")));
+    }
+
+    #[test]
+    fn test_find_readme_存在しない場合はnone() {
+        let temp_dir = tempdir().unwrap();
+        assert_eq!(find_readme(temp_dir.path()), None);
+    }
+
+    #[test]
+    fn test_render_markdown_見出しと強調にスタイルが付く() {
+        // Act
+        let lines = render_markdown("# Title\n\nSome **bold** and *italic* `code`.");
+
+        // Assert
+        assert_eq!(
+            lines_to_string(&lines),
+            "Title\n\nSome bold and italic code."
+        );
+        assert!(lines[0].spans[0]
+            .style
+            .add_modifier
+            .contains(Modifier::BOLD | Modifier::UNDERLINED));
+        let bold = lines[2].spans.iter().find(|s| s.content == "bold").unwrap();
+        assert!(bold.style.add_modifier.contains(Modifier::BOLD));
+        let italic = lines[2]
+            .spans
+            .iter()
+            .find(|s| s.content == "italic")
+            .unwrap();
+        assert!(italic.style.add_modifier.contains(Modifier::ITALIC));
+        let code = lines[2].spans.iter().find(|s| s.content == "code").unwrap();
+        assert_eq!(code.style.fg, Some(Color::Cyan));
+    }
+
+    #[test]
+    fn test_render_markdown_コードブロックとリストを描画する() {
+        // Act
+        let lines = render_markdown("- one\n1. two\n```rust\nfn main() {}\n```\n");
+
+        // Assert
+        assert_eq!(lines_to_string(&lines), "• one\n1. two\n  fn main() {}");
+        assert_eq!(lines[2].spans[1].style.fg, Some(Color::Cyan));
+    }
+
+    #[test]
+    fn test_render_markdown_リンクと画像とhtmlを整形する() {
+        // Act
+        let lines = render_markdown(
+            "<p align=\"center\">\n[docs](https://example.com) ![logo](logo.png) snake_case_name",
+        );
+
+        // Assert
+        assert_eq!(lines_to_string(&lines), "docs [logo] snake_case_name");
+    }
+
+    #[test]
+    fn test_render_readme_最大行数で切り詰める() {
+        // Arrange
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("README.txt");
+        let content = (1..=10)
+            .map(|i| format!("line {}\n", i))
+            .collect::<String>();
+        fs::write(&path, content).unwrap();
+
+        // Act
+        let preview_str = lines_to_string(&render_readme(&path, 3));
+
+        // Assert
+        assert!(preview_str.contains("README.txt"));
+        assert!(preview_str.contains("line 3"));
+        assert!(!preview_str.contains("line 4"));
+        assert!(preview_str.ends_with("..."));
+    }
+
+    #[test]
+    fn test_render_readme_rstの見出しを太字にする() {
+        // Arrange
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("README.rst");
+        fs::write(&path, "=====\nTitle\n=====\n\nBody\n").unwrap();
+
+        // Act
+        let lines = render_readme(&path, 10);
+
+        // Assert
+        let title = lines
+            .iter()
+            .find(|l| lines_to_string(std::slice::from_ref(l)) == "Title");
+        assert!(title.unwrap().style.add_modifier.contains(Modifier::BOLD));
+        assert!(!lines_to_string(&lines).contains("====="));
+    }
+}
@@ -0,0 +1,272 @@
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use super::archive::{ArchiveEntry, ArchiveFormat};
+use super::directory::DirectoryEntry;
+use super::file::{file_icon, FileEntry};
+use super::project::project_badges;
+use super::repository::RepositoryEntry;
+use super::{Entry, EntryKind};
+use crate::config::PreviewConfig;
+use crate::Pavo;
+
+/// 登録するエントリの種類
+///
+/// `detect`が`true`を返した種類のうち、`priority`が最も大きいものが使われる
+#[derive(Clone, Copy)]
+pub struct EntryKindRegistration {
+    /// 種類
+    pub kind: EntryKind,
+    /// 検索クエリの`kind:`などで種類の名前の代わりに使える別名
+    pub aliases: &'static [&'static str],
+    /// 判定の優先度（大きいほど先に判定する）
+    pub priority: i32,
+    /// パスがこの種類かどうかを判定する
+    pub detect: fn(&Path) -> bool,
+    /// エントリを作成せずに種類を表すアイコンを取得する（`Entry::icon`と同じものを返す）
+    pub icon: fn(&Path) -> &'static str,
+    /// エントリを作成せずに種類を補足するバッジを取得する（`Entry::badges`と同じものを返す）
+    pub badges: fn(&Path) -> Vec<String>,
+    /// プレビューの設定からエントリを作成する
+    pub create: fn(PathBuf, &PreviewConfig) -> Box<dyn Entry>,
+}
+
+/// パスに対応する`Entry`の実装を選ぶレジストリ
+#[derive(Default)]
+pub struct EntryRegistry {
+    /// 優先度の降順に並べた種類
+    kinds: Vec<EntryKindRegistration>,
+}
+
+impl EntryRegistry {
+    /// 組み込みの種類を登録したレジストリを作成する
+    pub fn builtin() -> Self {
+        let mut registry = Self::default();
+        registry.register(EntryKindRegistration {
+            kind: EntryKind::ARCHIVE,
+            aliases: &[],
+            priority: 30,
+            detect: |path| path.is_file() && ArchiveFormat::detect(path).is_some(),
+            icon: |_| ArchiveEntry::ICON,
+            badges: |path| {
+                ArchiveFormat::detect(path)
+                    .map(|format| vec![format.name().to_string()])
+                    .unwrap_or_default()
+            },
+            create: |path, config| match ArchiveFormat::detect(&path) {
+                Some(format) => Box::new(ArchiveEntry::from_config(path, format, config)),
+                None => Box::new(FileEntry::from_config(path, config)),
+            },
+        });
+        registry.register(EntryKindRegistration {
+            kind: EntryKind::REPOSITORY,
+            aliases: &["repo"],
+            priority: 20,
+            detect: |path| path.is_dir() && Pavo::is_git_repo(path),
+            icon: |_| RepositoryEntry::ICON,
+            badges: project_badges,
+            create: |path, config| Box::new(RepositoryEntry::from_config(path, config)),
+        });
+        registry.register(EntryKindRegistration {
+            kind: EntryKind::DIRECTORY,
+            aliases: &["dir"],
+            priority: 10,
+            detect: Path::is_dir,
+            icon: |_| DirectoryEntry::ICON,
+            badges: project_badges,
+            create: |path, config| {
+                // リポジトリ内のサブディレクトリは、それを含むリポジトリの情報と合わせて表示する
+                let repo = RepositoryEntry::from_config(path.clone(), config);
+                if repo.has_repository() {
+                    Box::new(repo)
+                } else {
+                    Box::new(DirectoryEntry::from_config(path, config))
+                }
+            },
+        });
+        registry.register(EntryKindRegistration {
+            kind: EntryKind::FILE,
+            aliases: &[],
+            priority: 0,
+            detect: |_| true,
+            icon: file_icon,
+            badges: |_| Vec::new(),
+            create: |path, config| Box::new(FileEntry::from_config(path, config)),
+        });
+        registry
+    }
+
+    /// 種類を登録する（同じ優先度の場合は先に登録したものが優先される）
+    pub fn register(&mut self, registration: EntryKindRegistration) {
+        let position = self
+            .kinds
+            .iter()
+            .position(|kind| kind.priority < registration.priority)
+            .unwrap_or(self.kinds.len());
+        self.kinds.insert(position, registration);
+    }
+
+    /// パスに一致する種類を判定する
+    pub fn detect(&self, path: &Path) -> Option<&EntryKindRegistration> {
+        self.kinds.iter().find(|kind| (kind.detect)(path))
+    }
+
+    /// 名前または別名から種類を探す
+    pub fn find(&self, name: &str) -> Option<&EntryKindRegistration> {
+        self.kinds
+            .iter()
+            .find(|kind| kind.kind.name() == name || kind.aliases.contains(&name))
+    }
+
+    /// パスに一致する種類のエントリを作成する
+    pub fn create(&self, path: &Path, config: &PreviewConfig) -> Option<Box<dyn Entry>> {
+        self.detect(path)
+            .map(|kind| (kind.create)(path.to_path_buf(), config))
+    }
+}
+
+/// 組み込みの種類を登録した共有のレジストリ
+///
+/// パスの一覧、検索クエリの`kind:`、プレビューのすべてで使われる。
+/// 種類を追加する場合は`EntryRegistry::builtin`で登録する
+pub fn registry() -> &'static EntryRegistry {
+    static REGISTRY: OnceLock<EntryRegistry> = OnceLock::new();
+    REGISTRY.get_or_init(EntryRegistry::builtin)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helper::{lines_to_string, setup_test_repo};
+    use ratatui::text::Line;
+    use tempfile::tempdir;
+
+    const MARKER: EntryKind = EntryKind::new("marker");
+
+    struct MarkerEntry;
+
+    fn marker_registration() -> EntryKindRegistration {
+        EntryKindRegistration {
+            kind: MARKER,
+            aliases: &["mark"],
+            priority: 15,
+            detect: |path| path.join(".marker").exists(),
+            icon: |_| "*",
+            badges: |_| vec!["marked".to_string()],
+            create: |_, _| Box::new(MarkerEntry),
+        }
+    }
+
+    impl Entry for MarkerEntry {
+        fn get_preview(&self) -> Vec<Line<'static>> {
+            vec![Line::from("marker preview")]
+        }
+
+        fn kind(&self) -> EntryKind {
+            MARKER
+        }
+
+        fn icon(&self) -> &'static str {
+            "*"
+        }
+    }
+
+    #[test]
+    fn test_builtin_detects_kinds_by_priority() {
+        let dir = tempdir().unwrap();
+        let repo = tempdir().unwrap();
+        setup_test_repo(&repo);
+        let file = dir.path().join("notes.txt");
+        std::fs::write(&file, "").unwrap();
+        let archive = dir.path().join("release.tar.gz");
+        std::fs::write(&archive, "").unwrap();
+
+        let registry = EntryRegistry::builtin();
+        let name = |path: &Path| registry.detect(path).map(|kind| kind.kind.name());
+
+        assert_eq!(name(dir.path()), Some("directory"));
+        assert_eq!(name(repo.path()), Some("repository"));
+        assert_eq!(name(&file), Some("file"));
+        assert_eq!(name(&archive), Some("archive"));
+    }
+
+    #[test]
+    fn test_repository_subdirectory_is_directory_with_repository_preview() {
+        let repo = tempdir().unwrap();
+        setup_test_repo(&repo);
+        let sub_dir = repo.path().join("sub");
+        std::fs::create_dir(&sub_dir).unwrap();
+
+        let registry = EntryRegistry::builtin();
+        let entry = registry
+            .create(&sub_dir, &PreviewConfig::default())
+            .unwrap();
+
+        assert_eq!(
+            registry.detect(&sub_dir).map(|kind| kind.kind),
+            Some(EntryKind::DIRECTORY)
+        );
+        assert!(lines_to_string(&entry.get_preview()).contains("Branch: main"));
+    }
+
+    #[test]
+    fn test_registered_kind_takes_precedence_by_priority() {
+        let dir = tempdir().unwrap();
+        std::fs::write(dir.path().join(".marker"), "").unwrap();
+        let mut registry = EntryRegistry::builtin();
+        registry.register(marker_registration());
+
+        let entry = registry
+            .create(dir.path(), &PreviewConfig::default())
+            .unwrap();
+
+        assert_eq!(entry.kind(), MARKER);
+        assert_eq!(lines_to_string(&entry.get_preview()), "marker preview");
+    }
+
+    #[test]
+    fn test_entries_describe_their_kind() {
+        let dir = tempdir().unwrap();
+        let archive = dir.path().join("release.zip");
+        std::fs::write(&archive, "").unwrap();
+
+        let entry = registry()
+            .create(&archive, &PreviewConfig::default())
+            .unwrap();
+
+        assert_eq!(entry.kind(), EntryKind::ARCHIVE);
+        assert_eq!(entry.badges(), vec!["zip".to_string()]);
+    }
+
+    #[test]
+    fn test_icon_and_badges_match_entry_without_creating_entry() {
+        let dir = tempdir().unwrap();
+        let archive = dir.path().join("release.tar.gz");
+        std::fs::write(&archive, "").unwrap();
+        let source = dir.path().join("main.rs");
+        std::fs::write(&source, "").unwrap();
+        std::fs::write(dir.path().join("Cargo.toml"), "[package]").unwrap();
+        let registry = EntryRegistry::builtin();
+
+        for path in [archive.as_path(), source.as_path(), dir.path()] {
+            let registration = registry.detect(path).unwrap();
+            let entry = (registration.create)(path.to_path_buf(), &PreviewConfig::default());
+            assert_eq!((registration.icon)(path), entry.icon());
+            assert_eq!((registration.badges)(path), entry.badges());
+        }
+    }
+
+    #[test]
+    fn test_registered_kind_is_found_by_name_and_detection() {
+        let dir = tempdir().unwrap();
+        std::fs::write(dir.path().join(".marker"), "").unwrap();
+        let mut registry = EntryRegistry::builtin();
+        registry.register(marker_registration());
+
+        let kind = |registration: Option<&EntryKindRegistration>| registration.map(|r| r.kind);
+
+        assert_eq!(kind(registry.find("marker")), Some(MARKER));
+        assert_eq!(kind(registry.find("mark")), Some(MARKER));
+        assert_eq!(kind(registry.detect(dir.path())), Some(MARKER));
+    }
+}
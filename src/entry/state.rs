@@ -0,0 +1,227 @@
+use ratatui::{
+    style::{Color, Style},
+    text::{Line, Span},
+};
+use std::fmt;
+use std::fs::{self, File};
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use super::error_line;
+
+/// リムーバブルメディアやネットワークドライブのマウント先と、ボリュームまでの間にあるディレクトリの階層数
+///
+/// 例えば`/media/<user>/<volume>`の場合は`<user>`の1階層を挟む
+const MOUNT_ROOTS: [(&str, usize); 4] = [
+    ("/mnt", 0),
+    ("/Volumes", 0),
+    ("/media", 1),
+    ("/run/media", 1),
+];
+
+/// ブックマークしたパスを開けるかどうか
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathState {
+    Available,
+    /// パスが存在しない
+    Missing,
+    /// 権限がなく読み込めない
+    PermissionDenied,
+    /// パスを含むボリュームがマウントされていない
+    Unmounted,
+    /// その他の理由で読み込めない（シンボリックリンクのループや入出力エラーなど）
+    Unreadable,
+}
+
+impl PathState {
+    /// 開けないパスを表すアイコン
+    pub const UNAVAILABLE_ICON: &'static str = "🚫";
+
+    /// パスの状態を判定する
+    pub fn detect(path: &Path) -> Self {
+        let roots: Vec<(&Path, usize)> = MOUNT_ROOTS
+            .iter()
+            .map(|&(root, depth)| (Path::new(root), depth))
+            .collect();
+        Self::detect_with_mount_roots(path, &roots)
+    }
+
+    fn detect_with_mount_roots(path: &Path, mount_roots: &[(&Path, usize)]) -> Self {
+        let readable = match fs::metadata(path) {
+            Ok(metadata) if metadata.is_dir() => fs::read_dir(path).map(|_| ()),
+            Ok(_) => File::open(path).map(|_| ()),
+            Err(err) => Err(err),
+        };
+        match readable {
+            Ok(()) => Self::Available,
+            Err(err) => match err.kind() {
+                ErrorKind::PermissionDenied => Self::PermissionDenied,
+                ErrorKind::NotFound if is_on_unmounted_volume(path, mount_roots) => Self::Unmounted,
+                // 途中のパスがファイルの場合も、そのパスは存在しない
+                ErrorKind::NotFound | ErrorKind::NotADirectory => Self::Missing,
+                _ => Self::Unreadable,
+            },
+        }
+    }
+
+    pub fn is_available(self) -> bool {
+        self == Self::Available
+    }
+
+    /// 一覧に表示する状態名
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Available => "available",
+            Self::Missing => "missing",
+            Self::PermissionDenied => "permission denied",
+            Self::Unmounted => "unmounted",
+            Self::Unreadable => "unreadable",
+        }
+    }
+
+    /// プレビューに表示する説明
+    fn description(self) -> &'static str {
+        match self {
+            Self::Available => "",
+            Self::Missing => "This path no longer exists. Run `pavo clean` to remove it, or edit it with `pavo config` if it is marked as persist.",
+            Self::PermissionDenied => "You do not have permission to read this path.",
+            Self::Unmounted => "The volume containing this path is not mounted or not reachable. Mount it and try again.",
+            Self::Unreadable => "This path could not be read. It may be a symbolic link loop or on a disconnected drive.",
+        }
+    }
+}
+
+/// パスがマウント先の下にあり、ボリューム自体が見つからないかどうか
+fn is_on_unmounted_volume(path: &Path, mount_roots: &[(&Path, usize)]) -> bool {
+    let Some(existing) = path.ancestors().find(|ancestor| ancestor.exists()) else {
+        return false;
+    };
+    mount_roots.iter().any(|&(root, depth)| {
+        path.strip_prefix(root).is_ok()
+            && existing
+                .strip_prefix(root)
+                .is_ok_and(|rest| rest.components().count() <= depth)
+    })
+}
+
+/// 開けないパスを選択した時のエラー
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnavailablePathError {
+    pub path: PathBuf,
+    pub state: PathState,
+}
+
+impl fmt::Display for UnavailablePathError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} is not available ({})",
+            self.path.display(),
+            self.state.name()
+        )
+    }
+}
+
+impl std::error::Error for UnavailablePathError {}
+
+/// 開けないパスの状態を説明するプレビュー
+pub fn unavailable_preview(path: &Path, state: PathState) -> Vec<Line<'static>> {
+    vec![
+        error_line(format!("{} ({})", path.display(), state.name())),
+        Line::from(""),
+        Line::from(Span::styled(
+            state.description(),
+            Style::default().fg(Color::DarkGray),
+        )),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helper::lines_to_string;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_detect_存在するパスは利用可能() {
+        let dir = tempdir().unwrap();
+        let file = dir.path().join("file.txt");
+        fs::write(&file, "").unwrap();
+
+        assert_eq!(PathState::detect(dir.path()), PathState::Available);
+        assert_eq!(PathState::detect(&file), PathState::Available);
+    }
+
+    #[test]
+    fn test_detect_存在しないパスはmissing() {
+        let dir = tempdir().unwrap();
+        assert_eq!(
+            PathState::detect(&dir.path().join("deleted")),
+            PathState::Missing
+        );
+    }
+
+    #[test]
+    fn test_detect_途中のパスがファイルの場合はmissing() {
+        let dir = tempdir().unwrap();
+        let file = dir.path().join("file.txt");
+        fs::write(&file, "").unwrap();
+
+        assert_eq!(PathState::detect(&file.join("child")), PathState::Missing);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_detect_シンボリックリンクのループはunreadable() {
+        let dir = tempdir().unwrap();
+        let link = dir.path().join("loop");
+        std::os::unix::fs::symlink(&link, &link).unwrap();
+
+        assert_eq!(PathState::detect(&link), PathState::Unreadable);
+    }
+
+    #[test]
+    fn test_detect_マウント先のボリュームが無い場合はunmounted() {
+        // Arrange
+        let media = tempdir().unwrap();
+        fs::create_dir(media.path().join("user")).unwrap();
+        fs::create_dir_all(media.path().join("user").join("mounted")).unwrap();
+        let roots = [(media.path(), 1)];
+
+        // Act & Assert
+        assert_eq!(
+            PathState::detect_with_mount_roots(
+                &media.path().join("user").join("usb").join("project"),
+                &roots
+            ),
+            PathState::Unmounted
+        );
+        assert_eq!(
+            PathState::detect_with_mount_roots(
+                &media.path().join("user").join("mounted").join("project"),
+                &roots
+            ),
+            PathState::Missing
+        );
+    }
+
+    #[test]
+    fn test_unavailable_path_error_状態を含むメッセージになる() {
+        let error = UnavailablePathError {
+            path: PathBuf::from("/mnt/usb/project"),
+            state: PathState::Unmounted,
+        };
+        assert_eq!(
+            error.to_string(),
+            "/mnt/usb/project is not available (unmounted)"
+        );
+    }
+
+    #[test]
+    fn test_unavailable_preview_状態と説明を表示する() {
+        let preview = unavailable_preview(Path::new("/gone"), PathState::Missing);
+        let preview_str = lines_to_string(&preview);
+        assert!(preview_str.contains("/gone (missing)"));
+        assert!(preview_str.contains("no longer exists"));
+    }
+}
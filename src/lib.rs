@@ -8,9 +8,11 @@ pub mod config;
 pub mod entry;
 pub mod path_display;
 pub mod pavo;
+pub mod search;
 pub mod shell;
 #[cfg(test)]
 pub mod test_helper;
+pub mod time_display;
 pub mod tui;
 
 pub fn run() -> anyhow::Result<()> {
@@ -20,10 +22,17 @@ pub fn run() -> anyhow::Result<()> {
     let tag_filter = cli.tag.clone();
 
     match cli.command {
-        Some(cli::Commands::Add { dir, persist }) => match dir {
-            Some(d) => pavo.add_path(&d, persist),
-            None => pavo.add_path(std::env::current_dir()?.to_str().unwrap(), persist),
-        },
+        Some(cli::Commands::Add { dir, persist, root }) => {
+            let dir = match dir {
+                Some(d) => d,
+                None => std::env::current_dir()?.to_str().unwrap().to_string(),
+            };
+            if root {
+                pavo.add_repo_root(&dir, persist)
+            } else {
+                pavo.add_path(&dir, persist)
+            }
+        }
         Some(cli::Commands::Config) => {
             let config_file = pavo.get_config_file();
             let editor = std::env::var("EDITOR")
@@ -1,6 +1,10 @@
-use crate::config::{Config, ConfigPath};
+use crate::config::{Config, ConfigPath, PreviewConfig};
 use crate::entry::{
-    directory::DirectoryEntry, file::FileEntry, repository::RepositoryEntry, Entry,
+    error_line,
+    external::{ExternalEntry, PreviewHandler},
+    metadata::metadata_header,
+    registry::registry,
+    state::{unavailable_preview, PathState},
 };
 use anyhow::{Context, Result};
 use git2::Repository;
@@ -27,35 +31,121 @@ impl Pavo {
         })
     }
 
-    pub fn get_entry_preview(path: &Path) -> Result<Vec<Line<'static>>> {
-        if path.is_dir() {
-            if Self::is_git_repo(path) {
-                Ok(RepositoryEntry::new(path.to_path_buf()).get_preview())
-            } else {
-                Ok(DirectoryEntry::new(path.to_path_buf(), None, None).get_preview())
+    /// パスのプレビューを生成する
+    ///
+    /// # Arguments
+    /// * `path` - 対象のパス
+    /// * `config` - プレビューの設定
+    /// * `bookmark` - ヘッダーに統計を表示するブックマーク（ブックマークされていないパスの場合は`None`）
+    pub fn get_entry_preview(
+        path: &Path,
+        config: &PreviewConfig,
+        bookmark: Option<&ConfigPath>,
+    ) -> Result<Vec<Line<'static>>> {
+        let state = PathState::detect(path);
+        // 種類の判定は1回だけ行い、エントリの作成とハンドラーの選択の両方に使う
+        let registration = state
+            .is_available()
+            .then(|| registry().detect(path).copied())
+            .flatten();
+        let Some(registration) = registration else {
+            let body = unavailable_preview(path, state);
+            return Ok(Self::with_metadata_header(
+                path,
+                state.name(),
+                bookmark,
+                body,
+                config,
+            ));
+        };
+
+        let entry = (registration.create)(path.to_path_buf(), config);
+        let body = match PreviewHandler::find(&config.handlers, path, registration.kind) {
+            Some(handler) => {
+                let external =
+                    ExternalEntry::new(path.to_path_buf(), handler.clone(), config.file_lines);
+                match external.run() {
+                    Ok(lines) => lines,
+                    // 外部コマンドが使えない場合は組み込みのプレビューで代替する
+                    Err(err) => {
+                        let mut lines =
+                            vec![error_line(format!("Preview command failed: {}", err))];
+                        lines.extend(entry.get_preview());
+                        lines
+                    }
+                }
             }
-        } else {
-            Ok(FileEntry::new(path.to_path_buf(), None).get_preview())
+            None => entry.get_preview(),
+        };
+
+        let mut kind = format!("{} {}", entry.icon(), entry.kind().name());
+        let badges = entry.badges();
+        if !badges.is_empty() {
+            kind.push_str(&format!(" ({})", badges.join(", ")));
         }
+        Ok(Self::with_metadata_header(
+            path, &kind, bookmark, body, config,
+        ))
+    }
+
+    /// 設定で有効な場合はプレビューの先頭にメタデータのヘッダーを追加する
+    fn with_metadata_header(
+        path: &Path,
+        kind: &str,
+        bookmark: Option<&ConfigPath>,
+        body: Vec<Line<'static>>,
+        config: &PreviewConfig,
+    ) -> Vec<Line<'static>> {
+        if !config.metadata {
+            return body;
+        }
+        let mut preview = metadata_header(path, kind, bookmark);
+        preview.extend(body);
+        preview
     }
 
+    /// ディレクトリがgitリポジトリのルートかどうか
+    ///
+    /// リポジトリ内のサブディレクトリは含まない（それを含むリポジトリを探す場合は`find_repo_root`を使う）
     pub fn is_git_repo(dir: &Path) -> bool {
         Repository::open(dir).is_ok()
     }
 
+    /// パスを含むリポジトリの作業ディレクトリのルートを探す
+    pub fn find_repo_root(path: &Path) -> Option<PathBuf> {
+        let repo = Repository::discover(path).ok()?;
+        repo.workdir().map(Path::to_path_buf)
+    }
+
     pub fn add_path(&mut self, path: &str, persist: bool) -> Result<()> {
-        let path = PathBuf::from(path);
-        let absolute_path = if path.is_absolute() {
-            path
-        } else {
-            std::env::current_dir()?.join(path)
-        };
-        let canonical_path = absolute_path.canonicalize()?;
+        let canonical_path = Self::to_absolute_path(path)?.canonicalize()?;
         self.config.add_path(canonical_path, persist)?;
         self.config.save(&self.config_file)?;
         Ok(())
     }
 
+    /// パスを含むリポジトリのルートを追加する
+    pub fn add_repo_root(&mut self, path: &str, persist: bool) -> Result<()> {
+        let absolute_path = Self::to_absolute_path(path)?;
+        let root = Self::find_repo_root(&absolute_path).with_context(|| {
+            format!("{} is not inside a git repository", absolute_path.display())
+        })?;
+        self.add_path(&root.to_string_lossy(), persist)
+    }
+
+    fn to_absolute_path(path: &str) -> Result<PathBuf> {
+        let path = PathBuf::from(path);
+        if path.is_absolute() {
+            Ok(path)
+        } else {
+            Ok(std::env::current_dir()?.join(path))
+        }
+    }
+
+    pub fn get_config(&self) -> &Config {
+        &self.config
+    }
+
     pub fn get_config_file(&self) -> &PathBuf {
         &self.config_file
     }
@@ -195,7 +285,7 @@ mod tests {
         File::create(&child_file).unwrap();
         let result = pavo.add_path(temp_dir.path().to_str().unwrap(), false);
         assert!(result.is_ok());
-        let result = Pavo::get_entry_preview(temp_dir.path());
+        let result = Pavo::get_entry_preview(temp_dir.path(), &PreviewConfig::default(), None);
         assert!(result.is_ok());
         let preview_str = lines_to_string(&result.unwrap());
         assert!(preview_str.contains(child_file.file_name().unwrap().to_str().unwrap()));
@@ -208,7 +298,7 @@ mod tests {
         let repo = test_helper::setup_test_repo(&temp_dir);
         let result = pavo.add_path(temp_dir.path().to_str().unwrap(), false);
         assert!(result.is_ok());
-        let result = Pavo::get_entry_preview(repo.path());
+        let result = Pavo::get_entry_preview(repo.path(), &PreviewConfig::default(), None);
         assert!(result.is_ok());
         let preview_str = lines_to_string(&result.unwrap());
         assert!(preview_str.contains("Branch"));
@@ -222,12 +312,158 @@ mod tests {
         write!(File::create(&file).unwrap(), "test content").unwrap();
         let result = pavo.add_path(temp_dir.path().to_str().unwrap(), false);
         assert!(result.is_ok());
-        let result = Pavo::get_entry_preview(file.as_path());
+        let result = Pavo::get_entry_preview(file.as_path(), &PreviewConfig::default(), None);
         assert!(result.is_ok());
         let preview_str = lines_to_string(&result.unwrap());
         assert!(preview_str.contains("test content"));
     }
 
+    #[test]
+    fn test_can_get_entry_preview_of_repo_subdirectory() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        test_helper::setup_test_repo(&temp_dir);
+        let sub_dir = temp_dir.path().join("crates").join("foo");
+        std::fs::create_dir_all(&sub_dir).unwrap();
+        File::create(sub_dir.join("lib.rs")).unwrap();
+        let result = Pavo::get_entry_preview(&sub_dir, &PreviewConfig::default(), None);
+        let preview_str = lines_to_string(&result.unwrap());
+        assert!(preview_str.contains("Branch: main"));
+        assert!(preview_str.contains("Path: crates/foo"));
+        assert!(preview_str.contains("lib.rs"));
+    }
+
+    #[test]
+    fn test_can_get_entry_preview_of_archive() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let archive = temp_dir.path().join("release.tar");
+        let mut builder = tar::Builder::new(File::create(&archive).unwrap());
+        let mut header = tar::Header::new_gnu();
+        header.set_size(4);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, "release/app", &b"data"[..])
+            .unwrap();
+        builder.finish().unwrap();
+        drop(builder);
+
+        let result = Pavo::get_entry_preview(&archive, &PreviewConfig::default(), None);
+        let preview_str = lines_to_string(&result.unwrap());
+        assert!(preview_str.contains("Archive (tar)"));
+        assert!(preview_str.contains("app (4 B)"));
+    }
+
+    #[test]
+    fn test_entry_preview_starts_with_metadata_header() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file = temp_dir.path().join("notes.txt");
+        std::fs::write(&file, "notes").unwrap();
+        let bookmark = ConfigPath {
+            path: file.clone(),
+            last_selected: chrono::Utc::now(),
+            persist: false,
+            tags: vec!["docs".to_string()],
+            access_count: 2,
+        };
+
+        let preview =
+            Pavo::get_entry_preview(&file, &PreviewConfig::default(), Some(&bookmark)).unwrap();
+        let preview_str = lines_to_string(&preview);
+        assert!(preview_str.contains("Kind: 📝 file"));
+        assert!(preview_str.contains("Tags: docs"));
+        assert!(preview_str.find("Kind:") < preview_str.rfind("notes"));
+
+        let config = PreviewConfig {
+            metadata: false,
+            ..PreviewConfig::default()
+        };
+        let preview = Pavo::get_entry_preview(&file, &config, Some(&bookmark)).unwrap();
+        assert!(!lines_to_string(&preview).contains("Kind:"));
+    }
+
+    #[test]
+    fn test_entry_preview_uses_matching_handler_and_falls_back_to_builtin() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file = temp_dir.path().join("data.json");
+        std::fs::write(&file, "{\"builtin\": true}").unwrap();
+        let handler = |command: &str| PreviewHandler {
+            glob: Some("*.json".parse().unwrap()),
+            mime: None,
+            kind: None,
+            command: command.to_string(),
+            timeout_ms: 1000,
+        };
+        let config = |command: &str| PreviewConfig {
+            metadata: false,
+            handlers: vec![handler(command)],
+            ..PreviewConfig::default()
+        };
+
+        let preview = Pavo::get_entry_preview(&file, &config("echo external"), None).unwrap();
+        let preview_str = lines_to_string(&preview);
+        assert!(preview_str.contains("external"));
+        assert!(!preview_str.contains("builtin"));
+
+        let preview = Pavo::get_entry_preview(&file, &config("exit 1 #"), None).unwrap();
+        let preview_str = lines_to_string(&preview);
+        assert!(preview_str.contains("Preview command failed"));
+        assert!(preview_str.contains("builtin"));
+    }
+
+    #[test]
+    fn test_entry_preview_of_missing_path_explains_state() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let missing = temp_dir.path().join("deleted");
+        let result = Pavo::get_entry_preview(&missing, &PreviewConfig::default(), None);
+        let preview_str = lines_to_string(&result.unwrap());
+        assert!(preview_str.contains("(missing)"));
+    }
+
+    #[test]
+    fn test_is_git_repo_only_matches_repository_root() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        test_helper::setup_test_repo(&temp_dir);
+        let sub_dir = temp_dir.path().join("sub");
+        std::fs::create_dir(&sub_dir).unwrap();
+        assert!(Pavo::is_git_repo(temp_dir.path()));
+        assert!(!Pavo::is_git_repo(&sub_dir));
+    }
+
+    #[test]
+    fn test_find_repo_root() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        test_helper::setup_test_repo(&temp_dir);
+        let sub_dir = temp_dir.path().join("sub");
+        std::fs::create_dir(&sub_dir).unwrap();
+        let root = Pavo::find_repo_root(&sub_dir).unwrap();
+        assert_eq!(
+            root.canonicalize().unwrap(),
+            temp_dir.path().canonicalize().unwrap()
+        );
+        let not_repo = tempfile::tempdir().unwrap();
+        assert!(Pavo::find_repo_root(not_repo.path()).is_none());
+    }
+
+    #[test]
+    fn test_can_add_repo_root() {
+        let (mut pavo, _temp_config_dir) = setup();
+        let temp_dir = tempfile::tempdir().unwrap();
+        test_helper::setup_test_repo(&temp_dir);
+        let sub_dir = temp_dir.path().join("sub");
+        std::fs::create_dir(&sub_dir).unwrap();
+        pavo.add_repo_root(sub_dir.to_str().unwrap(), false)
+            .unwrap();
+        assert!(pavo.contains(&temp_dir.path().canonicalize().unwrap()));
+        assert!(!pavo.contains(&sub_dir.canonicalize().unwrap()));
+    }
+
+    #[test]
+    fn test_cant_add_repo_root_outside_repository() {
+        let (mut pavo, _temp_config_dir) = setup();
+        let temp_dir = tempfile::tempdir().unwrap();
+        let result = pavo.add_repo_root(temp_dir.path().to_str().unwrap(), false);
+        assert!(result.is_err());
+    }
+
     #[test]
     fn test_can_get_config_file() {
         let (mut pavo, _temp_config_dir) = setup();
@@ -0,0 +1,3 @@
+// サブモジュール定義
+pub mod matcher;
+pub mod query;
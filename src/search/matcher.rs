@@ -0,0 +1,291 @@
+use fuzzy_matcher::clangd::ClangdMatcher;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+
+/// 検索語のマッチアルゴリズム
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MatcherAlgorithm {
+    /// skim v2のファジーマッチ
+    #[default]
+    Skim,
+    /// clangd方式のファジーマッチ
+    Clangd,
+    /// 部分文字列の完全一致
+    Exact,
+    /// 正規表現
+    Regex,
+}
+
+impl MatcherAlgorithm {
+    /// 次のアルゴリズムを取得する
+    pub fn next(self) -> Self {
+        match self {
+            Self::Skim => Self::Clangd,
+            Self::Clangd => Self::Exact,
+            Self::Exact => Self::Regex,
+            Self::Regex => Self::Skim,
+        }
+    }
+
+    /// アルゴリズム名を取得する
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Skim => "skim",
+            Self::Clangd => "clangd",
+            Self::Exact => "exact",
+            Self::Regex => "regex",
+        }
+    }
+}
+
+/// 大文字小文字の扱い
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CaseMatching {
+    /// 検索語に大文字が含まれる場合のみ区別する
+    #[default]
+    Smart,
+    /// 常に区別する
+    Respect,
+    /// 常に区別しない
+    Ignore,
+}
+
+impl CaseMatching {
+    /// 次の設定を取得する
+    pub fn next(self) -> Self {
+        match self {
+            Self::Smart => Self::Respect,
+            Self::Respect => Self::Ignore,
+            Self::Ignore => Self::Smart,
+        }
+    }
+
+    /// 設定名を取得する
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Smart => "smart-case",
+            Self::Respect => "respect-case",
+            Self::Ignore => "ignore-case",
+        }
+    }
+}
+
+/// 設定に応じて検索語を文字列にマッチさせる
+pub struct Matcher {
+    algorithm: MatcherAlgorithm,
+    case: CaseMatching,
+    skim: SkimMatcherV2,
+    clangd: ClangdMatcher,
+    /// 直前にコンパイルした正規表現（パターンとコンパイル結果）
+    regex_cache: RefCell<Option<(String, Option<Regex>)>>,
+}
+
+impl Default for Matcher {
+    fn default() -> Self {
+        Self::new(MatcherAlgorithm::default(), CaseMatching::default())
+    }
+}
+
+impl Matcher {
+    /// 新しいMatcherを作成する
+    ///
+    /// # Arguments
+    /// * `algorithm` - マッチアルゴリズム
+    /// * `case` - 大文字小文字の扱い
+    pub fn new(algorithm: MatcherAlgorithm, case: CaseMatching) -> Self {
+        let (skim, clangd) = match case {
+            CaseMatching::Smart => (
+                SkimMatcherV2::default().smart_case(),
+                ClangdMatcher::default().smart_case(),
+            ),
+            CaseMatching::Respect => (
+                SkimMatcherV2::default().respect_case(),
+                ClangdMatcher::default().respect_case(),
+            ),
+            CaseMatching::Ignore => (
+                SkimMatcherV2::default().ignore_case(),
+                ClangdMatcher::default().ignore_case(),
+            ),
+        };
+        Self {
+            algorithm,
+            case,
+            skim,
+            clangd,
+            regex_cache: RefCell::new(None),
+        }
+    }
+
+    pub fn algorithm(&self) -> MatcherAlgorithm {
+        self.algorithm
+    }
+
+    pub fn case(&self) -> CaseMatching {
+        self.case
+    }
+
+    /// 検索語に対して大文字小文字を無視するかどうか
+    pub fn ignore_case(&self, pattern: &str) -> bool {
+        match self.case {
+            CaseMatching::Smart => !pattern.chars().any(char::is_uppercase),
+            CaseMatching::Respect => false,
+            CaseMatching::Ignore => true,
+        }
+    }
+
+    /// 検索語を文字列にマッチさせる
+    ///
+    /// # Returns
+    /// マッチした場合は`text`内でマッチした文字位置
+    pub fn match_indices(&self, text: &str, pattern: &str) -> Option<Vec<usize>> {
+        match self.algorithm {
+            MatcherAlgorithm::Skim => self
+                .skim
+                .fuzzy_indices(text, pattern)
+                .map(|(_, indices)| indices),
+            MatcherAlgorithm::Clangd => self
+                .clangd
+                .fuzzy_indices(text, pattern)
+                .map(|(_, indices)| indices),
+            MatcherAlgorithm::Exact => {
+                let ignore_case = self.ignore_case(pattern);
+                let text: Vec<char> = text.chars().collect();
+                let pattern: Vec<char> = pattern.chars().collect();
+                find_chars(&text, &pattern, ignore_case)
+                    .map(|start| (start..start + pattern.len()).collect())
+            }
+            MatcherAlgorithm::Regex => self.regex_indices(text, pattern),
+        }
+    }
+
+    /// 正規表現でマッチさせる（不正な正規表現はマッチしない扱いにする）
+    fn regex_indices(&self, text: &str, pattern: &str) -> Option<Vec<usize>> {
+        let mut cache = self.regex_cache.borrow_mut();
+        if cache.as_ref().map(|(cached, _)| cached.as_str()) != Some(pattern) {
+            let regex = RegexBuilder::new(pattern)
+                .case_insensitive(self.ignore_case(pattern))
+                .build()
+                .ok();
+            *cache = Some((pattern.to_string(), regex));
+        }
+        let regex = cache.as_ref().and_then(|(_, regex)| regex.as_ref())?;
+        let found = regex.find(text)?;
+        let start = text[..found.start()].chars().count();
+        let len = found.as_str().chars().count();
+        Some((start..start + len).collect())
+    }
+}
+
+fn char_eq(a: char, b: char, ignore_case: bool) -> bool {
+    if ignore_case {
+        a == b || a.to_lowercase().eq(b.to_lowercase())
+    } else {
+        a == b
+    }
+}
+
+/// `text`の`start`位置から`pattern`が一致するかどうか
+pub(crate) fn chars_eq_at(
+    text: &[char],
+    pattern: &[char],
+    start: usize,
+    ignore_case: bool,
+) -> bool {
+    text.len() >= start + pattern.len()
+        && pattern
+            .iter()
+            .zip(&text[start..])
+            .all(|(&p, &t)| char_eq(t, p, ignore_case))
+}
+
+/// `text`内で最初に`pattern`が出現する文字位置を探す
+pub(crate) fn find_chars(text: &[char], pattern: &[char], ignore_case: bool) -> Option<usize> {
+    (0..=text.len().checked_sub(pattern.len())?)
+        .find(|&start| chars_eq_at(text, pattern, start, ignore_case))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_algorithm_next_全てのアルゴリズムを巡回する() {
+        let mut algorithm = MatcherAlgorithm::Skim;
+        let mut names = Vec::new();
+        for _ in 0..4 {
+            names.push(algorithm.name());
+            algorithm = algorithm.next();
+        }
+        assert_eq!(names, vec!["skim", "clangd", "exact", "regex"]);
+        assert_eq!(algorithm, MatcherAlgorithm::Skim);
+    }
+
+    #[test]
+    fn test_case_next_全ての設定を巡回する() {
+        assert_eq!(CaseMatching::Smart.next(), CaseMatching::Respect);
+        assert_eq!(CaseMatching::Respect.next(), CaseMatching::Ignore);
+        assert_eq!(CaseMatching::Ignore.next(), CaseMatching::Smart);
+    }
+
+    #[test]
+    fn test_match_indices_skimはファジーマッチする() {
+        let matcher = Matcher::new(MatcherAlgorithm::Skim, CaseMatching::Smart);
+        assert_eq!(
+            matcher.match_indices("pavo-cli", "pcl"),
+            Some(vec![0, 5, 6])
+        );
+    }
+
+    #[test]
+    fn test_match_indices_clangdはファジーマッチする() {
+        let matcher = Matcher::new(MatcherAlgorithm::Clangd, CaseMatching::Smart);
+        assert!(matcher.match_indices("pavo-cli", "pcli").is_some());
+        assert!(matcher.match_indices("pavo-cli", "xyz").is_none());
+    }
+
+    #[test]
+    fn test_match_indices_exactは部分文字列のみマッチする() {
+        let matcher = Matcher::new(MatcherAlgorithm::Exact, CaseMatching::Smart);
+        assert_eq!(
+            matcher.match_indices("pavo-cli", "cli"),
+            Some(vec![5, 6, 7])
+        );
+        assert!(matcher.match_indices("pavo-cli", "pcl").is_none());
+    }
+
+    #[test]
+    fn test_match_indices_regexはマッチ範囲の文字位置を返す() {
+        let matcher = Matcher::new(MatcherAlgorithm::Regex, CaseMatching::Smart);
+        assert_eq!(
+            matcher.match_indices("日本/pavo-2", r"o-\d"),
+            Some(vec![6, 7, 8])
+        );
+        assert!(matcher.match_indices("pavo", r"^\d").is_none());
+    }
+
+    #[test]
+    fn test_match_indices_不正な正規表現はマッチしない() {
+        let matcher = Matcher::new(MatcherAlgorithm::Regex, CaseMatching::Smart);
+        assert!(matcher.match_indices("pavo", "(").is_none());
+    }
+
+    #[test]
+    fn test_match_indices_smart_caseは大文字を含む場合のみ区別する() {
+        let matcher = Matcher::new(MatcherAlgorithm::Exact, CaseMatching::Smart);
+        assert!(matcher.match_indices("Pavo", "pavo").is_some());
+        assert!(matcher.match_indices("pavo", "Pavo").is_none());
+    }
+
+    #[test]
+    fn test_match_indices_respectとignoreの設定に従う() {
+        let respect = Matcher::new(MatcherAlgorithm::Skim, CaseMatching::Respect);
+        let ignore = Matcher::new(MatcherAlgorithm::Regex, CaseMatching::Ignore);
+        assert!(respect.match_indices("Pavo", "pavo").is_none());
+        assert!(ignore.match_indices("pavo", "PAVO").is_some());
+    }
+}
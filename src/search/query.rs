@@ -0,0 +1,562 @@
+use serde::{Deserialize, Serialize};
+
+use super::matcher::{chars_eq_at, find_chars, Matcher, MatcherAlgorithm};
+use crate::entry::EntryKind;
+
+/// 検索クエリの構文木
+///
+/// スペース区切りの各条件をANDで結合したもの。
+///
+/// # 構文
+/// - `foo` - ファジーマッチ（`Matcher`の設定に従う）
+/// - `'foo` - 完全一致（部分文字列）
+/// - `^foo` - 前方一致
+/// - `foo$` - 後方一致
+/// - `^foo$` - 全体一致
+/// - `!foo` - 否定（`foo`を含まない）。他の条件の前にも付けられる
+/// - `#tag` - タグの前方一致
+/// - `kind:repo|dir|file|archive` - エントリの種類（レジストリに登録された種類の名前か別名）
+/// - `persist:yes|no` - persistフラグ
+///
+/// 正規表現のアルゴリズムでは`^`・`$`などが正規表現のアンカーと重なるため、
+/// `'`・`^`・`$`・`!`は解釈せずに検索語ごと正規表現に渡す
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Query {
+    terms: Vec<Term>,
+}
+
+/// クエリを構成する1つの条件
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Term {
+    /// 条件を否定するかどうか
+    pub negated: bool,
+    /// 条件の内容
+    pub condition: Condition,
+}
+
+/// 条件の内容
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Condition {
+    Fuzzy(String),
+    Exact(String),
+    Prefix(String),
+    Suffix(String),
+    Equal(String),
+    Tag(String),
+    Kind(EntryKind),
+    Persist(bool),
+}
+
+/// 検索語のマッチ対象にするフィールド
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchField {
+    /// 表示用の短縮パス
+    Display,
+    /// 正規化された絶対パス
+    Path,
+    /// タグ
+    Tags,
+}
+
+/// クエリの評価対象
+pub struct Candidate<'a> {
+    /// 表示用の短縮パス（ハイライト位置の基準）
+    pub display: &'a str,
+    /// 正規化された絶対パス
+    pub path: &'a str,
+    /// タグのリスト
+    pub tags: &'a [String],
+    /// エントリの種類（判定が終わっていない場合は`None`）
+    pub kind: Option<EntryKind>,
+    /// persistフラグ
+    pub persist: bool,
+}
+
+impl Query {
+    /// 入力文字列をクエリに変換する
+    ///
+    /// 値が空の条件（`'`や`#`のみなど）は無視する。
+    /// 解釈できない`kind:`・`persist:`の値は通常の検索語として扱う。
+    pub fn parse(input: &str) -> Self {
+        let terms = input.split_whitespace().filter_map(Term::parse).collect();
+        Self { terms }
+    }
+
+    /// マッチアルゴリズムに合わせて入力文字列をクエリに変換する
+    ///
+    /// 正規表現の場合は、`#`・`kind:`・`persist:`以外の検索語をそのまま正規表現として扱う
+    pub fn parse_with(input: &str, algorithm: MatcherAlgorithm) -> Self {
+        if algorithm != MatcherAlgorithm::Regex {
+            return Self::parse(input);
+        }
+        let terms = input
+            .split_whitespace()
+            .map(|token| Term {
+                negated: false,
+                condition: Condition::parse_filter(token)
+                    .unwrap_or_else(|| Condition::Fuzzy(token.to_string())),
+            })
+            .collect();
+        Self { terms }
+    }
+
+    /// 条件が1つも無いかどうか
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    /// 条件のリストを取得する
+    pub fn terms(&self) -> &[Term] {
+        &self.terms
+    }
+
+    /// 候補がクエリにマッチするか評価する
+    ///
+    /// 検索語は`scope`の順にフィールドを調べ、最初にマッチしたフィールドを採用する。
+    ///
+    /// # Arguments
+    /// * `candidate` - 評価対象
+    /// * `scope` - 検索語のマッチ対象にするフィールド
+    /// * `matcher` - 検索語のマッチャー
+    ///
+    /// # Returns
+    /// マッチした場合は`display`内でハイライトする文字位置（昇順・重複なし）
+    pub fn matches(
+        &self,
+        candidate: &Candidate,
+        scope: &[SearchField],
+        matcher: &Matcher,
+    ) -> Option<Vec<usize>> {
+        let mut indices = Vec::new();
+        for term in &self.terms {
+            let result = term.condition.evaluate(candidate, scope, matcher);
+            match (term.negated, result) {
+                (false, Some(matched)) => indices.extend(matched),
+                (true, None) => {}
+                _ => return None,
+            }
+        }
+        indices.sort_unstable();
+        indices.dedup();
+        Some(indices)
+    }
+}
+
+impl Term {
+    fn parse(token: &str) -> Option<Self> {
+        let (negated, body) = match token.strip_prefix('!') {
+            Some(rest) => (true, rest),
+            None => (false, token),
+        };
+        Condition::parse(body, negated).map(|condition| Self { negated, condition })
+    }
+}
+
+impl Condition {
+    fn parse(body: &str, negated: bool) -> Option<Self> {
+        if body.starts_with('#') {
+            return Self::parse_filter(body);
+        }
+        if let Some(filter) = Self::parse_filter(body) {
+            return Some(filter);
+        }
+        if let Some(exact) = body.strip_prefix('\'') {
+            return non_empty(exact).map(|t| Self::Exact(t.to_string()));
+        }
+        match (body.strip_prefix('^'), body.strip_suffix('$')) {
+            (Some(rest), Some(_)) => non_empty(rest.strip_suffix('$').unwrap_or(rest))
+                .map(|t| Self::Equal(t.to_string())),
+            (Some(rest), None) => non_empty(rest).map(|t| Self::Prefix(t.to_string())),
+            (None, Some(rest)) => non_empty(rest).map(|t| Self::Suffix(t.to_string())),
+            // fzfと同様に、否定された通常の検索語は完全一致として扱う
+            (None, None) if negated => non_empty(body).map(|t| Self::Exact(t.to_string())),
+            (None, None) => non_empty(body).map(|t| Self::Fuzzy(t.to_string())),
+        }
+    }
+
+    /// タグ・種類・persistフラグの絞り込みを解析する（`#`のみの場合は`None`）
+    fn parse_filter(body: &str) -> Option<Self> {
+        if let Some(tag) = body.strip_prefix('#') {
+            return non_empty(tag).map(|t| Self::Tag(t.to_string()));
+        }
+        if let Some(kind) = body.strip_prefix("kind:").and_then(EntryKind::from_name) {
+            return Some(Self::Kind(kind));
+        }
+        body.strip_prefix("persist:")
+            .and_then(parse_bool)
+            .map(Self::Persist)
+    }
+
+    /// 条件を評価する
+    ///
+    /// # Returns
+    /// マッチした場合は`display`内でハイライトする文字位置
+    fn evaluate(
+        &self,
+        candidate: &Candidate,
+        scope: &[SearchField],
+        matcher: &Matcher,
+    ) -> Option<Vec<usize>> {
+        match self {
+            Self::Tag(prefix) => {
+                let prefix = prefix.to_lowercase();
+                candidate
+                    .tags
+                    .iter()
+                    .any(|tag| tag.to_lowercase().starts_with(&prefix))
+                    .then(Vec::new)
+            }
+            Self::Kind(kind) => (candidate.kind == Some(*kind)).then(Vec::new),
+            Self::Persist(persist) => (candidate.persist == *persist).then(Vec::new),
+            _ => scope
+                .iter()
+                .find_map(|&field| self.evaluate_field(field, candidate, matcher)),
+        }
+    }
+
+    /// 検索語を1つのフィールドに対して評価する
+    fn evaluate_field(
+        &self,
+        field: SearchField,
+        candidate: &Candidate,
+        matcher: &Matcher,
+    ) -> Option<Vec<usize>> {
+        match field {
+            SearchField::Display => self.evaluate_text(candidate.display, matcher),
+            SearchField::Path => self
+                .evaluate_text(candidate.path, matcher)
+                .map(|indices| path_indices_to_display(&indices, candidate)),
+            SearchField::Tags => candidate
+                .tags
+                .iter()
+                .find_map(|tag| self.evaluate_text(tag, matcher))
+                .map(|_| Vec::new()),
+        }
+    }
+
+    /// 検索語を文字列に対して評価する
+    ///
+    /// # Returns
+    /// マッチした場合は`text`内の文字位置
+    fn evaluate_text(&self, text_str: &str, matcher: &Matcher) -> Option<Vec<usize>> {
+        let text: Vec<char> = text_str.chars().collect();
+        match self {
+            Self::Fuzzy(pattern) => matcher.match_indices(text_str, pattern),
+            Self::Exact(pattern) => {
+                let (pattern, ignore_case) = pattern_chars(pattern, matcher);
+                find_chars(&text, &pattern, ignore_case)
+                    .map(|start| (start..start + pattern.len()).collect())
+            }
+            Self::Prefix(pattern) => {
+                let (pattern, ignore_case) = pattern_chars(pattern, matcher);
+                chars_eq_at(&text, &pattern, 0, ignore_case).then(|| (0..pattern.len()).collect())
+            }
+            Self::Suffix(pattern) => {
+                let (pattern, ignore_case) = pattern_chars(pattern, matcher);
+                let start = text.len().checked_sub(pattern.len())?;
+                chars_eq_at(&text, &pattern, start, ignore_case)
+                    .then(|| (start..text.len()).collect())
+            }
+            Self::Equal(pattern) => {
+                let (pattern, ignore_case) = pattern_chars(pattern, matcher);
+                (text.len() == pattern.len() && chars_eq_at(&text, &pattern, 0, ignore_case))
+                    .then(|| (0..text.len()).collect())
+            }
+            Self::Tag(_) | Self::Kind(_) | Self::Persist(_) => None,
+        }
+    }
+}
+
+/// 絶対パス内の文字位置を表示用の短縮パス内の文字位置に変換する
+///
+/// 短縮パスは絶対パスの末尾部分なので、その範囲に含まれる位置のみを残す
+fn path_indices_to_display(indices: &[usize], candidate: &Candidate) -> Vec<usize> {
+    if !candidate.path.ends_with(candidate.display) {
+        return Vec::new();
+    }
+    let offset = candidate.path.chars().count() - candidate.display.chars().count();
+    indices
+        .iter()
+        .filter(|&&i| i >= offset)
+        .map(|&i| i - offset)
+        .collect()
+}
+
+fn non_empty(s: &str) -> Option<&str> {
+    if s.is_empty() {
+        None
+    } else {
+        Some(s)
+    }
+}
+
+fn parse_bool(value: &str) -> Option<bool> {
+    match value {
+        "yes" | "true" => Some(true),
+        "no" | "false" => Some(false),
+        _ => None,
+    }
+}
+
+/// パターンを文字のベクタに変換し、大文字小文字を無視するかどうかと合わせて返す
+fn pattern_chars(pattern: &str, matcher: &Matcher) -> (Vec<char>, bool) {
+    (pattern.chars().collect(), matcher.ignore_case(pattern))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate<'a>(text: &'a str, tags: &'a [String]) -> Candidate<'a> {
+        Candidate {
+            display: text,
+            path: text,
+            tags,
+            kind: Some(EntryKind::DIRECTORY),
+            persist: false,
+        }
+    }
+
+    fn matches(query: &str, candidate: &Candidate) -> Option<Vec<usize>> {
+        Query::parse(query).matches(candidate, &[SearchField::Display], &Matcher::default())
+    }
+
+    #[test]
+    fn test_parse_各構文が構文木に変換される() {
+        // Act
+        let query = Query::parse("foo 'bar ^baz qux$ ^all$ !neg #work kind:repo persist:yes");
+
+        // Assert
+        let conditions: Vec<_> = query
+            .terms()
+            .iter()
+            .map(|t| (t.negated, t.condition.clone()))
+            .collect();
+        assert_eq!(
+            conditions,
+            vec![
+                (false, Condition::Fuzzy("foo".to_string())),
+                (false, Condition::Exact("bar".to_string())),
+                (false, Condition::Prefix("baz".to_string())),
+                (false, Condition::Suffix("qux".to_string())),
+                (false, Condition::Equal("all".to_string())),
+                (true, Condition::Exact("neg".to_string())),
+                (false, Condition::Tag("work".to_string())),
+                (false, Condition::Kind(EntryKind::REPOSITORY)),
+                (false, Condition::Persist(true)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_否定は他の条件にも付けられる() {
+        // Act
+        let query = Query::parse("!#work !kind:file !^src");
+
+        // Assert
+        assert!(query.terms().iter().all(|t| t.negated));
+        assert_eq!(
+            query.terms()[0].condition,
+            Condition::Tag("work".to_string())
+        );
+        assert_eq!(query.terms()[1].condition, Condition::Kind(EntryKind::FILE));
+        assert_eq!(
+            query.terms()[2].condition,
+            Condition::Prefix("src".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_空の条件は無視される() {
+        // Act
+        let query = Query::parse("  ' ^ $ ! #  ");
+
+        // Assert
+        assert!(query.is_empty());
+    }
+
+    #[test]
+    fn test_parse_不明な修飾子の値は通常の検索語になる() {
+        // Act
+        let query = Query::parse("kind:unknown");
+
+        // Assert
+        assert_eq!(
+            query.terms()[0].condition,
+            Condition::Fuzzy("kind:unknown".to_string())
+        );
+    }
+
+    #[test]
+    fn test_matches_全ての条件をandで評価する() {
+        let c = candidate("pavo/src", &[]);
+        assert!(matches("pavo src", &c).is_some());
+        assert!(matches("pavo other", &c).is_none());
+    }
+
+    #[test]
+    fn test_matches_完全一致_前方一致_後方一致の位置を返す() {
+        let c = candidate("my-project", &[]);
+        assert_eq!(matches("'proj", &c), Some(vec![3, 4, 5, 6]));
+        assert_eq!(matches("^my", &c), Some(vec![0, 1]));
+        assert_eq!(matches("ect$", &c), Some(vec![7, 8, 9]));
+        assert!(matches("^proj", &c).is_none());
+        assert!(matches("my$", &c).is_none());
+        assert!(matches("^my-project$", &c).is_some());
+        assert!(matches("^my$", &c).is_none());
+    }
+
+    #[test]
+    fn test_matches_大文字を含む場合は大文字小文字を区別する() {
+        let c = candidate("MyProject", &[]);
+        assert!(matches("'project", &c).is_some());
+        assert!(matches("'Project", &c).is_some());
+        assert!(matches("'PROJECT", &c).is_none());
+    }
+
+    #[test]
+    fn test_matches_否定条件にマッチする候補は除外される() {
+        let c = candidate("pavo/target", &[]);
+        assert!(matches("pavo !target", &c).is_none());
+        assert!(matches("pavo !node", &c).is_some());
+    }
+
+    #[test]
+    fn test_matches_タグ_種類_persistで絞り込む() {
+        let tags = vec!["work".to_string(), "rust".to_string()];
+        let c = Candidate {
+            display: "pavo",
+            path: "/home/user/pavo",
+            tags: &tags,
+            kind: Some(EntryKind::REPOSITORY),
+            persist: true,
+        };
+        assert_eq!(matches("#wo kind:repo persist:yes", &c), Some(vec![]));
+        assert!(matches("#personal", &c).is_none());
+        assert!(matches("kind:file", &c).is_none());
+        assert!(matches("persist:no", &c).is_none());
+        assert!(matches("!#rust", &c).is_none());
+    }
+
+    #[test]
+    fn test_matches_マッチ位置は昇順で重複しない() {
+        let c = candidate("abcabc", &[]);
+        assert_eq!(matches("'bc ^abc", &c), Some(vec![0, 1, 2]));
+    }
+
+    #[test]
+    fn test_matches_スコープに含まれるフィールドのみを検索する() {
+        // Arrange
+        let tags = vec!["backend".to_string()];
+        let c = Candidate {
+            display: "pavo",
+            path: "/home/user/org/pavo",
+            tags: &tags,
+            kind: Some(EntryKind::DIRECTORY),
+            persist: false,
+        };
+        let matcher = Matcher::default();
+        let query = Query::parse("'org");
+        let tag_query = Query::parse("'backend");
+
+        // Act & Assert
+        assert!(query
+            .matches(&c, &[SearchField::Display], &matcher)
+            .is_none());
+        assert_eq!(
+            query.matches(&c, &[SearchField::Display, SearchField::Path], &matcher),
+            Some(vec![])
+        );
+        assert!(tag_query
+            .matches(&c, &[SearchField::Display, SearchField::Path], &matcher)
+            .is_none());
+        assert_eq!(
+            tag_query.matches(&c, &[SearchField::Tags], &matcher),
+            Some(vec![])
+        );
+    }
+
+    #[test]
+    fn test_matches_絶対パスでのマッチ位置は短縮パス上の位置に変換される() {
+        // Arrange
+        let c = Candidate {
+            display: "org/pavo",
+            path: "/home/user/org/pavo",
+            tags: &[],
+            kind: Some(EntryKind::DIRECTORY),
+            persist: false,
+        };
+
+        // Act
+        let result =
+            Query::parse("'user/org").matches(&c, &[SearchField::Path], &Matcher::default());
+
+        // Assert
+        assert_eq!(result, Some(vec![0, 1, 2]));
+    }
+
+    #[test]
+    fn test_matches_否定条件は全てのスコープで評価される() {
+        // Arrange
+        let c = Candidate {
+            display: "pavo",
+            path: "/home/user/org/pavo",
+            tags: &[],
+            kind: Some(EntryKind::DIRECTORY),
+            persist: false,
+        };
+        let scope = [SearchField::Display, SearchField::Path];
+
+        // Act
+        let result = Query::parse("pavo !org").matches(&c, &scope, &Matcher::default());
+
+        // Assert
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn test_parse_with_正規表現ではアンカーや記号を正規表現に渡す() {
+        // Arrange
+        let matcher = Matcher::new(
+            MatcherAlgorithm::Regex,
+            crate::search::matcher::CaseMatching::Smart,
+        );
+        let tags = vec!["work".to_string()];
+        let c = candidate("src/pavo.rs", &tags);
+        let evaluate = |input: &str| {
+            Query::parse_with(input, MatcherAlgorithm::Regex).matches(
+                &c,
+                &[SearchField::Display],
+                &matcher,
+            )
+        };
+
+        // Act
+        let query = Query::parse_with("^src rs$ #work", MatcherAlgorithm::Regex);
+
+        // Assert
+        assert_eq!(
+            query.terms(),
+            &[
+                Term {
+                    negated: false,
+                    condition: Condition::Fuzzy("^src".to_string()),
+                },
+                Term {
+                    negated: false,
+                    condition: Condition::Fuzzy("rs$".to_string()),
+                },
+                Term {
+                    negated: false,
+                    condition: Condition::Tag("work".to_string()),
+                },
+            ]
+        );
+        assert_eq!(evaluate("^src"), Some(vec![0, 1, 2]));
+        assert_eq!(evaluate("rs$"), Some(vec![9, 10]));
+        assert_eq!(evaluate("^s.c/p"), Some(vec![0, 1, 2, 3, 4]));
+        assert!(evaluate("^pavo").is_none());
+        assert!(evaluate("src$").is_none());
+    }
+}
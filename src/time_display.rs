@@ -0,0 +1,68 @@
+use chrono::{DateTime, Duration, Utc};
+
+/// 現在時刻からの経過時間を「3 days ago」のような相対表記にする
+///
+/// # Arguments
+/// * `time` - 対象の時刻
+/// * `now` - 現在時刻
+pub fn format_relative(time: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    format_elapsed(now - time)
+}
+
+/// 経過時間を相対表記にする（未来の時刻は「just now」として扱う）
+pub fn format_elapsed(elapsed: Duration) -> String {
+    let seconds = elapsed.num_seconds();
+    if seconds < 60 {
+        return "just now".to_string();
+    }
+
+    let units = [
+        (60 * 60 * 24 * 365, "year"),
+        (60 * 60 * 24 * 30, "month"),
+        (60 * 60 * 24 * 7, "week"),
+        (60 * 60 * 24, "day"),
+        (60 * 60, "hour"),
+        (60, "minute"),
+    ];
+    let (count, unit) = units
+        .iter()
+        .find(|(unit_seconds, _)| seconds >= *unit_seconds)
+        .map(|(unit_seconds, unit)| (seconds / unit_seconds, *unit))
+        .unwrap_or((seconds / 60, "minute"));
+    if count == 1 {
+        format!("1 {} ago", unit)
+    } else {
+        format!("{} {}s ago", count, unit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_elapsed_1分未満はjust_nowになる() {
+        assert_eq!(format_elapsed(Duration::seconds(59)), "just now");
+        assert_eq!(format_elapsed(Duration::seconds(-10)), "just now");
+    }
+
+    #[test]
+    fn test_format_elapsed_最も大きい単位で表記する() {
+        assert_eq!(format_elapsed(Duration::minutes(1)), "1 minute ago");
+        assert_eq!(format_elapsed(Duration::minutes(59)), "59 minutes ago");
+        assert_eq!(format_elapsed(Duration::hours(3)), "3 hours ago");
+        assert_eq!(format_elapsed(Duration::days(1)), "1 day ago");
+        assert_eq!(format_elapsed(Duration::days(14)), "2 weeks ago");
+        assert_eq!(format_elapsed(Duration::days(61)), "2 months ago");
+        assert_eq!(format_elapsed(Duration::days(800)), "2 years ago");
+    }
+
+    #[test]
+    fn test_format_relative_現在時刻との差を表記する() {
+        let now = Utc::now();
+        assert_eq!(
+            format_relative(now - Duration::hours(5), now),
+            "5 hours ago"
+        );
+    }
+}
@@ -1,8 +1,13 @@
 // サブモジュール定義
 mod app;
+mod attributes;
 mod event;
 mod focus;
+mod icons;
+mod keymap;
+mod preview;
 mod runner;
+mod theme;
 mod ui;
 
 // 公開API
@@ -1,12 +1,27 @@
-use fuzzy_matcher::skim::SkimMatcherV2;
-use fuzzy_matcher::FuzzyMatcher;
+use git2::Repository;
 use ratatui::text::Line;
 use std::path::PathBuf;
 
+use crate::config::{ConfigPath, IconStyle, ListConfig, PreviewConfig, SearchConfig};
+use crate::entry::repository::collect_linked_paths;
+use crate::entry::state::PathState;
 use crate::path_display;
+use crate::search::matcher::Matcher;
+use crate::search::query::{Candidate, Query, SearchField};
 use crate::Pavo;
 
+use super::attributes::{AttributeLoader, DetectedAttributes};
 use super::focus::{FocusedPanel, ModalFocus};
+use super::icons;
+use super::keymap::{KeyChord, Keymap};
+use super::preview::{self, PreviewLoader};
+use super::theme::Theme;
+
+/// `show_more_preview_lines`で一度に増やす行数
+const PREVIEW_LINES_STEP: usize = 10;
+
+/// 描画前に半ページの移動量として使う行数
+const DEFAULT_PAGE_HEIGHT: usize = 20;
 
 /// TUIアプリケーションの状態を管理する構造体
 pub struct App {
@@ -14,22 +29,44 @@ pub struct App {
     paths: Vec<PathBuf>,
     /// 表示用の短縮パスのリスト
     display_paths: Vec<String>,
+    /// 検索クエリの評価に使うパスごとの属性
+    attributes: Vec<PathAttributes>,
     /// フィルタリング後のパスのインデックスとマッチ位置
     filtered_indices: Vec<(usize, Vec<usize>)>,
     /// 選択中のアイテムのインデックス
     selected: usize,
+    /// パスの種類・状態・バッジをバックグラウンドで判定するローダー
+    attribute_loader: AttributeLoader,
     /// 入力中のクエリ
     input: String,
     /// 検索入力のカーソル位置（文字単位）
     input_cursor: usize,
-    /// ファジーマッチャー
-    matcher: SkimMatcherV2,
+    /// 検索語のマッチャー
+    matcher: Matcher,
+    /// 検索語のマッチ対象にするフィールド
+    search_scope: Vec<SearchField>,
+    /// パス一覧に表示するアイコンの種類
+    icon_style: IconStyle,
+    /// 描画に使う配色
+    theme: Theme,
+    /// キーと操作の対応
+    keymap: Keymap,
+    /// 続きのキーを待っている入力途中のキーの並び
+    pending_keys: Vec<KeyChord>,
+    /// PathsとPreviewパネルに表示できる行数（半ページの移動量に使う）
+    page_height: usize,
     /// アプリケーションを終了するかどうか
     should_quit: bool,
     /// 選択されたパス
     selected_path: Option<PathBuf>,
     /// プレビューテキスト（色付き）
     preview: Vec<Line<'static>>,
+    /// バックグラウンドでプレビューを生成するローダー
+    preview_loader: PreviewLoader,
+    /// プレビューの設定
+    preview_config: PreviewConfig,
+    /// プレビューを折り返して表示するかどうか
+    preview_wrap: bool,
     /// プレビューのスクロールオフセット
     preview_scroll: u16,
     /// フォーカス中のパネル
@@ -53,6 +90,22 @@ pub struct App {
     modal_original_tags: String,
 }
 
+/// 検索クエリの評価に使うパスの属性
+#[derive(Debug, Clone)]
+struct PathAttributes {
+    path: String,
+    tags: Vec<String>,
+    persist: bool,
+    /// バックグラウンドで判定した種類・状態・バッジ（判定が終わるまでは`None`）
+    detected: Option<DetectedAttributes>,
+    /// プレビューのヘッダーに統計を表示するブックマークの設定
+    bookmark: Option<ConfigPath>,
+    /// ブックマークされていない、リポジトリのワークツリーやサブモジュールの行かどうか
+    linked: bool,
+    /// ワークツリーやサブモジュールの行を展開しているかどうか
+    expanded: bool,
+}
+
 impl App {
     /// 新しいAppインスタンスを作成する
     ///
@@ -63,23 +116,47 @@ impl App {
         let filtered_indices: Vec<(usize, Vec<usize>)> =
             (0..paths.len()).map(|i| (i, vec![])).collect();
         let display_paths = path_display::compute_display_paths(&paths);
-        let preview = if !paths.is_empty() {
-            Pavo::get_entry_preview(&paths[0]).unwrap_or_default()
-        } else {
-            vec![]
-        };
+        // 種類や状態の判定はファイルシステムへのアクセスで遅くなることがあるため、
+        // 最初の描画を待たせないようバックグラウンドで行う
+        let mut attribute_loader = AttributeLoader::new();
+        let attributes = paths
+            .iter()
+            .map(|path| {
+                attribute_loader.request(path.clone());
+                PathAttributes {
+                    path: path.to_string_lossy().into_owned(),
+                    tags: Vec::new(),
+                    persist: false,
+                    detected: None,
+                    bookmark: None,
+                    linked: false,
+                    expanded: false,
+                }
+            })
+            .collect();
 
-        Self {
+        let mut app = Self {
             paths,
             display_paths,
+            attributes,
+            attribute_loader,
             filtered_indices,
             selected: 0,
             input: String::new(),
             input_cursor: 0,
-            matcher: SkimMatcherV2::default(),
+            matcher: Matcher::default(),
+            search_scope: SearchConfig::default().scope,
+            icon_style: IconStyle::default(),
+            theme: Theme::default(),
+            keymap: Keymap::default(),
+            pending_keys: Vec::new(),
+            page_height: DEFAULT_PAGE_HEIGHT,
             should_quit: false,
             selected_path: None,
-            preview,
+            preview: vec![],
+            preview_loader: PreviewLoader::new(),
+            preview_config: PreviewConfig::default(),
+            preview_wrap: true,
             preview_scroll: 0,
             focused_panel: FocusedPanel::Search,
             show_modal: false,
@@ -90,40 +167,347 @@ impl App {
             modal_focus: ModalFocus::Persist,
             modal_original_persist: false,
             modal_original_tags: String::new(),
+        };
+        app.update_preview();
+        app
+    }
+
+    /// 設定ファイルのタグとpersist値を検索用の属性として取り込み、ブックマークの統計をプレビューに表示する
+    ///
+    /// # Arguments
+    /// * `config_paths` - 設定ファイルのパス情報
+    pub fn with_config_paths(mut self, config_paths: &[ConfigPath]) -> Self {
+        for (path, attributes) in self.paths.iter().zip(self.attributes.iter_mut()) {
+            if let Some(config_path) = config_paths.iter().find(|cp| cp.path == *path) {
+                attributes.tags = config_path.tags.clone();
+                attributes.persist = config_path.persist;
+                attributes.bookmark = Some(config_path.clone());
+            }
         }
+        self.update_preview();
+        self
+    }
+
+    /// 検索の設定を適用する
+    ///
+    /// # Arguments
+    /// * `search_config` - 検索の設定
+    pub fn with_search_config(mut self, search_config: &SearchConfig) -> Self {
+        self.search_scope = search_config.scope.clone();
+        self.matcher = Matcher::new(search_config.algorithm, search_config.case);
+        self
+    }
+
+    /// パス一覧の設定を適用する
+    pub fn with_list_config(mut self, list_config: &ListConfig) -> Self {
+        self.icon_style = list_config.icons;
+        self
+    }
+
+    /// 描画に使う配色を設定する
+    pub fn with_theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    /// キーと操作の対応を設定する
+    pub fn with_keymap(mut self, keymap: Keymap) -> Self {
+        self.keymap = keymap;
+        self
+    }
+
+    /// プレビューの設定を反映する
+    pub fn with_preview_config(mut self, preview_config: &PreviewConfig) -> Self {
+        self.preview_config = preview_config.clone();
+        self.update_preview();
+        self
+    }
+
+    /// マッチアルゴリズムを次のものに切り替えて再フィルタリングする
+    pub fn cycle_matcher_algorithm(&mut self) {
+        self.matcher = Matcher::new(self.matcher.algorithm().next(), self.matcher.case());
+        self.filter_paths();
+    }
+
+    /// 大文字小文字の扱いを次のものに切り替えて再フィルタリングする
+    pub fn cycle_case_matching(&mut self) {
+        self.matcher = Matcher::new(self.matcher.algorithm(), self.matcher.case().next());
+        self.filter_paths();
     }
 
     /// 入力クエリに基づいてパスをフィルタリングする
+    ///
+    /// 入力は`search::query::Query`の拡張構文として解釈する
     pub fn filter_paths(&mut self) {
-        if self.input.is_empty() {
+        self.apply_filter();
+        self.selected = 0;
+        self.update_preview();
+    }
+
+    /// 選択位置を変えずにフィルタリングし直す
+    ///
+    /// # Arguments
+    /// * `idx` - フィルタリング後に選択するパスのインデックス
+    fn refilter_selecting(&mut self, idx: usize) {
+        self.apply_filter();
+        self.selected = self
+            .filtered_indices
+            .iter()
+            .position(|&(i, _)| i == idx)
+            .unwrap_or(0);
+        self.update_preview();
+    }
+
+    fn apply_filter(&mut self) {
+        let query = Query::parse_with(&self.input, self.matcher.algorithm());
+        if query.is_empty() {
             self.filtered_indices = (0..self.paths.len()).map(|i| (i, vec![])).collect();
         } else {
             self.filtered_indices = self
                 .display_paths
                 .iter()
+                .zip(&self.attributes)
                 .enumerate()
-                .filter_map(|(i, display_path)| {
-                    self.matcher
-                        .fuzzy_indices(display_path, &self.input)
-                        .map(|(score, indices)| (i, score, indices))
+                .filter_map(|(i, (display_path, attributes))| {
+                    let candidate = Candidate {
+                        display: display_path,
+                        path: &attributes.path,
+                        tags: &attributes.tags,
+                        kind: attributes.detected.as_ref().map(|detected| detected.kind),
+                        persist: attributes.persist,
+                    };
+                    query
+                        .matches(&candidate, &self.search_scope, &self.matcher)
+                        .map(|indices| (i, indices))
                 })
-                .collect::<Vec<_>>()
-                .into_iter()
-                .map(|(i, _, indices)| (i, indices))
                 .collect();
         }
-        self.selected = 0;
-        self.update_preview();
+    }
+
+    /// 選択中のリポジトリのワークツリーとサブモジュールを子の行として展開する
+    ///
+    /// 子の行はブックマークされず、選択するとそのパスに移動できる
+    pub fn expand_selected(&mut self) {
+        let Some(&(idx, _)) = self.filtered_indices.get(self.selected) else {
+            return;
+        };
+        let attributes = &self.attributes[idx];
+        if attributes.linked || attributes.expanded {
+            return;
+        }
+        let linked = Repository::open(&self.paths[idx])
+            .map(|repo| collect_linked_paths(&repo))
+            .unwrap_or_default();
+        if linked.is_empty() {
+            return;
+        }
+
+        self.attributes[idx].expanded = true;
+        for (offset, entry) in linked.into_iter().enumerate() {
+            let position = idx + 1 + offset;
+            self.display_paths.insert(
+                position,
+                format!("  ↳ [{}] {}", entry.kind.name(), entry.name),
+            );
+            self.attribute_loader.request(entry.path.clone());
+            self.attributes.insert(
+                position,
+                PathAttributes {
+                    path: entry.path.to_string_lossy().into_owned(),
+                    tags: Vec::new(),
+                    persist: false,
+                    detected: None,
+                    bookmark: None,
+                    linked: true,
+                    expanded: false,
+                },
+            );
+            self.paths.insert(position, entry.path);
+        }
+        self.refilter_selecting(idx);
+    }
+
+    /// 選択中の行（または選択中の子の行の親）の展開を閉じる
+    pub fn collapse_selected(&mut self) {
+        let Some(&(selected_idx, _)) = self.filtered_indices.get(self.selected) else {
+            return;
+        };
+        let Some(idx) = (0..=selected_idx)
+            .rev()
+            .find(|&i| !self.attributes[i].linked)
+        else {
+            return;
+        };
+        if !self.attributes[idx].expanded {
+            return;
+        }
+
+        self.attributes[idx].expanded = false;
+        let end = (idx + 1..self.paths.len())
+            .find(|&i| !self.attributes[i].linked)
+            .unwrap_or(self.paths.len());
+        self.paths.drain(idx + 1..end);
+        self.display_paths.drain(idx + 1..end);
+        self.attributes.drain(idx + 1..end);
+        self.refilter_selecting(idx);
+    }
+
+    /// 指定したパスがワークツリーやサブモジュールの子の行かどうか
+    pub fn is_linked(&self, idx: usize) -> bool {
+        self.attributes.get(idx).is_some_and(|a| a.linked)
+    }
+
+    /// 指定したパスを開けるかどうか（判定が終わっていない場合は開けるものとする）
+    pub fn path_state(&self, idx: usize) -> PathState {
+        self.detected(idx)
+            .map_or(PathState::Available, |detected| detected.state)
+    }
+
+    /// 指定したパスの前に表示するアイコン（表示幅を揃えた文字列）
+    ///
+    /// 種類の判定が終わっていない場合は、アイコンの幅の空白を返す
+    pub fn icon(&self, idx: usize) -> Option<String> {
+        match self.detected(idx) {
+            Some(detected) => icons::icon(
+                self.icon_style,
+                &self.paths[idx],
+                detected.kind,
+                detected.state,
+            ),
+            None => icons::placeholder(self.icon_style),
+        }
+    }
+
+    /// 指定したパスのエントリの種類のバッジ
+    pub fn badges(&self, idx: usize) -> &[String] {
+        self.detected(idx)
+            .map_or(&[], |detected| detected.badges.as_slice())
+    }
+
+    fn detected(&self, idx: usize) -> Option<&DetectedAttributes> {
+        self.attributes.get(idx)?.detected.as_ref()
+    }
+
+    /// バックグラウンドで判定したパスの種類・状態・バッジを反映する
+    ///
+    /// `kind:`の条件の結果が変わるため、検索中の場合は選択中のパスを保ったままフィルタリングし直す
+    pub fn poll_attributes(&mut self) {
+        let detected = self.attribute_loader.poll();
+        if detected.is_empty() {
+            return;
+        }
+        for (path, attributes) in detected {
+            for (idx, _) in self.paths.iter().enumerate().filter(|(_, p)| **p == path) {
+                self.attributes[idx].detected = Some(attributes.clone());
+            }
+        }
+        if self.input.is_empty() {
+            return;
+        }
+        let selected = self
+            .filtered_indices
+            .get(self.selected)
+            .map(|&(idx, _)| idx);
+        self.apply_filter();
+        match selected.and_then(|idx| self.filtered_indices.iter().position(|&(i, _)| i == idx)) {
+            Some(position) => self.selected = position,
+            None => {
+                self.selected = 0;
+                self.update_preview();
+            }
+        }
+    }
+
+    /// パスの種類や状態を判定中かどうか
+    pub fn is_detecting_attributes(&self) -> bool {
+        self.attribute_loader.is_pending()
+    }
+
+    /// 指定したパスの子の行を展開しているかどうか
+    pub fn is_expanded(&self, idx: usize) -> bool {
+        self.attributes.get(idx).is_some_and(|a| a.expanded)
     }
 
     /// プレビューを更新する
+    ///
+    /// プレビューはバックグラウンドで生成し、生成が終わるまではキャッシュ済みの
+    /// プレビューかプレースホルダーを表示する
     pub fn update_preview(&mut self) {
+        self.refresh_preview();
+        self.preview_scroll = 0;
+    }
+
+    /// スクロール位置を保ったままプレビューを再生成する
+    fn refresh_preview(&mut self) {
         if let Some(&(idx, _)) = self.filtered_indices.get(self.selected) {
-            self.preview = Pavo::get_entry_preview(&self.paths[idx]).unwrap_or_default();
+            self.preview = self
+                .preview_loader
+                .request(
+                    &self.paths[idx],
+                    &self.preview_config,
+                    self.attributes[idx].bookmark.as_ref(),
+                )
+                .unwrap_or_else(preview::loading_placeholder);
         } else {
+            self.preview_loader.cancel();
             self.preview = vec![];
         }
-        self.preview_scroll = 0;
+    }
+
+    pub fn preview_config(&self) -> &PreviewConfig {
+        &self.preview_config
+    }
+
+    pub fn preview_wrap(&self) -> bool {
+        self.preview_wrap
+    }
+
+    /// ディレクトリツリーの深さを1つ増やす
+    pub fn increase_preview_depth(&mut self) {
+        self.preview_config.tree_depth += 1;
+        self.refresh_preview();
+    }
+
+    /// ディレクトリツリーの深さを1つ減らす
+    pub fn decrease_preview_depth(&mut self) {
+        if self.preview_config.tree_depth > 0 {
+            self.preview_config.tree_depth -= 1;
+            self.refresh_preview();
+        }
+    }
+
+    /// ドットファイルの表示を切り替える
+    pub fn toggle_preview_hidden(&mut self) {
+        self.preview_config.show_hidden = !self.preview_config.show_hidden;
+        self.refresh_preview();
+    }
+
+    /// プレビューの折り返しを切り替える
+    pub fn toggle_preview_wrap(&mut self) {
+        self.preview_wrap = !self.preview_wrap;
+    }
+
+    /// ファイルのプレビューに表示する行数を増やす
+    pub fn show_more_preview_lines(&mut self) {
+        self.preview_config.file_lines += PREVIEW_LINES_STEP;
+        self.refresh_preview();
+    }
+
+    /// バックグラウンドで生成されたプレビューを反映する
+    ///
+    /// ディレクトリのサイズの計算が終わった場合は、サイズを表示するためにプレビューを再生成する
+    pub fn poll_preview(&mut self) {
+        if let Some(lines) = self.preview_loader.poll() {
+            self.preview = lines;
+        }
+        if self.preview_loader.is_size_ready() && !self.preview_loader.is_pending() {
+            self.refresh_preview();
+        }
+    }
+
+    /// プレビューを生成中かどうか
+    pub fn is_preview_loading(&self) -> bool {
+        self.preview_loader.is_pending()
     }
 
     /// 次のアイテムを選択する
@@ -146,6 +530,38 @@ impl App {
         }
     }
 
+    /// 最初のアイテムを選択する
+    pub fn select_first(&mut self) {
+        if !self.filtered_indices.is_empty() {
+            self.selected = 0;
+            self.update_preview();
+        }
+    }
+
+    /// 最後のアイテムを選択する
+    pub fn select_last(&mut self) {
+        if !self.filtered_indices.is_empty() {
+            self.selected = self.filtered_indices.len() - 1;
+            self.update_preview();
+        }
+    }
+
+    /// 半ページ下のアイテムを選択する（末尾で止まる）
+    pub fn select_half_page_down(&mut self) {
+        if !self.filtered_indices.is_empty() {
+            self.selected = (self.selected + self.half_page()).min(self.filtered_indices.len() - 1);
+            self.update_preview();
+        }
+    }
+
+    /// 半ページ上のアイテムを選択する（先頭で止まる）
+    pub fn select_half_page_up(&mut self) {
+        if !self.filtered_indices.is_empty() {
+            self.selected = self.selected.saturating_sub(self.half_page());
+            self.update_preview();
+        }
+    }
+
     /// 現在選択中のパスを確定する
     pub fn confirm_selection(&mut self) {
         if let Some(&(idx, _)) = self.filtered_indices.get(self.selected) {
@@ -218,6 +634,11 @@ impl App {
         self.focused_panel = self.focused_panel.previous();
     }
 
+    /// 指定したパネルにフォーカスを移動する
+    pub fn focus_panel(&mut self, panel: FocusedPanel) {
+        self.focused_panel = panel;
+    }
+
     /// プレビューを上にスクロールする
     pub fn scroll_preview_up(&mut self) {
         if self.preview_scroll > 0 {
@@ -230,9 +651,57 @@ impl App {
         self.preview_scroll += 1;
     }
 
+    /// プレビューの先頭までスクロールする
+    pub fn scroll_preview_top(&mut self) {
+        self.preview_scroll = 0;
+    }
+
+    /// プレビューの最後の行が表示されるまでスクロールする
+    pub fn scroll_preview_bottom(&mut self) {
+        let bottom = self.preview.len().saturating_sub(self.page_height);
+        self.preview_scroll = u16::try_from(bottom).unwrap_or(u16::MAX);
+    }
+
+    /// プレビューを半ページ下にスクロールする
+    pub fn scroll_preview_half_page_down(&mut self) {
+        let half_page = u16::try_from(self.half_page()).unwrap_or(u16::MAX);
+        self.preview_scroll = self.preview_scroll.saturating_add(half_page);
+    }
+
+    /// プレビューを半ページ上にスクロールする
+    pub fn scroll_preview_half_page_up(&mut self) {
+        let half_page = u16::try_from(self.half_page()).unwrap_or(u16::MAX);
+        self.preview_scroll = self.preview_scroll.saturating_sub(half_page);
+    }
+
+    /// PathsとPreviewパネルに表示できる行数を設定する
+    pub fn set_page_height(&mut self, page_height: usize) {
+        self.page_height = page_height;
+    }
+
+    /// 半ページの移動量（最低1行）
+    fn half_page(&self) -> usize {
+        (self.page_height / 2).max(1)
+    }
+
+    /// 入力途中のキーの並びを取り出す
+    pub fn take_pending_keys(&mut self) -> Vec<KeyChord> {
+        std::mem::take(&mut self.pending_keys)
+    }
+
+    /// 続きのキーを待つキーの並びを保存する
+    pub fn set_pending_keys(&mut self, keys: Vec<KeyChord>) {
+        self.pending_keys = keys;
+    }
+
     /// モーダルを開く
+    ///
+    /// ワークツリーやサブモジュールの子の行はブックマークではないため開かない
     pub fn open_modal(&mut self, pavo: &Pavo) {
         if let Some(&(idx, _)) = self.filtered_indices.get(self.selected) {
+            if self.is_linked(idx) {
+                return;
+            }
             let path = &self.paths[idx];
             if let Some(config_path) = pavo.get_paths().iter().find(|cp| cp.path == *path) {
                 self.modal_persist_value = config_path.persist;
@@ -262,15 +731,26 @@ impl App {
         self.modal_persist_value = !self.modal_persist_value;
     }
 
-    /// モーダルの変更を確定する
+    /// モーダルの変更を確定する（子の行の場合は何も変更しない）
     pub fn confirm_modal(&mut self) -> Option<(usize, bool, Vec<String>)> {
-        if let Some(&(idx, _)) = self.filtered_indices.get(self.selected) {
+        if let Some(&(idx, _)) = self
+            .filtered_indices
+            .get(self.selected)
+            .filter(|&&(idx, _)| !self.is_linked(idx))
+        {
             let tags: Vec<String> = self
                 .modal_tags_input
                 .split(',')
                 .map(|s| s.trim().to_string())
                 .filter(|s| !s.is_empty())
                 .collect();
+            // 検索用の属性も更新する
+            self.attributes[idx].tags = tags.clone();
+            self.attributes[idx].persist = self.modal_persist_value;
+            if let Some(bookmark) = &mut self.attributes[idx].bookmark {
+                bookmark.tags = tags.clone();
+                bookmark.persist = self.modal_persist_value;
+            }
             Some((idx, self.modal_persist_value, tags))
         } else {
             None
@@ -360,6 +840,10 @@ impl App {
         self.input_cursor
     }
 
+    pub fn matcher(&self) -> &Matcher {
+        &self.matcher
+    }
+
     pub fn should_quit(&self) -> bool {
         self.should_quit
     }
@@ -372,6 +856,14 @@ impl App {
         &self.preview
     }
 
+    pub fn theme(&self) -> &Theme {
+        &self.theme
+    }
+
+    pub fn keymap(&self) -> &Keymap {
+        &self.keymap
+    }
+
     pub fn preview_scroll(&self) -> u16 {
         self.preview_scroll
     }
@@ -410,6 +902,8 @@ impl App {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::search::matcher::{CaseMatching, MatcherAlgorithm};
+    use crate::test_helper::lines_to_string;
     use std::fs;
     use tempfile::TempDir;
 
@@ -422,6 +916,14 @@ mod tests {
         temp_dir
     }
 
+    /// 一時ディレクトリの名前にマッチしないよう、表示用の短縮パスだけを検索する設定
+    fn display_only() -> SearchConfig {
+        SearchConfig {
+            scope: vec![SearchField::Display],
+            ..SearchConfig::default()
+        }
+    }
+
     #[test]
     fn test_app_new_空のパスリストで初期化される() {
         // Arrange & Act
@@ -553,7 +1055,7 @@ mod tests {
             temp_dir.path().join("test2"),
             temp_dir.path().join("other"),
         ];
-        let mut app = App::new(paths, None);
+        let mut app = App::new(paths, None).with_search_config(&display_only());
 
         // Act
         app.input = "test".to_string();
@@ -689,6 +1191,260 @@ mod tests {
         assert_eq!(app.preview_scroll(), 6);
     }
 
+    /// パスの種類や状態の判定が終わるまで待つ
+    fn wait_for_attributes(app: &mut App) {
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+        while app.is_detecting_attributes() && std::time::Instant::now() < deadline {
+            app.poll_attributes();
+            std::thread::sleep(std::time::Duration::from_millis(5));
+        }
+    }
+
+    /// プレビューの生成が終わるまで待つ
+    fn wait_for_preview(app: &mut App) {
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+        while app.is_preview_loading() && std::time::Instant::now() < deadline {
+            app.poll_preview();
+            std::thread::sleep(std::time::Duration::from_millis(5));
+        }
+    }
+
+    #[test]
+    fn test_increase_preview_depth_深さが増えてプレビューが再生成される() {
+        // Arrange
+        let temp_dir = create_test_env();
+        fs::create_dir_all(temp_dir.path().join("test1/child/grandchild/leaf")).unwrap();
+        let mut app = App::new(vec![temp_dir.path().join("test1")], None);
+        wait_for_preview(&mut app);
+        assert!(!lines_to_string(app.preview()).contains("leaf"));
+
+        // Act
+        app.increase_preview_depth();
+        wait_for_preview(&mut app);
+
+        // Assert
+        assert_eq!(app.preview_config().tree_depth, 2);
+        assert!(lines_to_string(app.preview()).contains("leaf"));
+    }
+
+    #[test]
+    fn test_decrease_preview_depth_0より小さくならない() {
+        // Arrange
+        let temp_dir = create_test_env();
+        let mut app = App::new(vec![temp_dir.path().join("test1")], None);
+
+        // Act
+        app.decrease_preview_depth();
+        app.decrease_preview_depth();
+
+        // Assert
+        assert_eq!(app.preview_config().tree_depth, 0);
+    }
+
+    #[test]
+    fn test_toggle_preview_hidden_ドットファイルが表示される() {
+        // Arrange
+        let temp_dir = create_test_env();
+        fs::write(temp_dir.path().join("test1/.hidden"), "").unwrap();
+        let mut app = App::new(vec![temp_dir.path().join("test1")], None);
+        wait_for_preview(&mut app);
+
+        // Act
+        app.toggle_preview_hidden();
+        wait_for_preview(&mut app);
+
+        // Assert
+        assert!(app.preview_config().show_hidden);
+        assert!(lines_to_string(app.preview()).contains(".hidden"));
+    }
+
+    #[test]
+    fn test_toggle_preview_wrap_折り返しが切り替わる() {
+        // Arrange
+        let temp_dir = create_test_env();
+        let mut app = App::new(vec![temp_dir.path().join("test1")], None);
+
+        // Act
+        app.toggle_preview_wrap();
+
+        // Assert
+        assert!(!app.preview_wrap());
+    }
+
+    #[test]
+    fn test_show_more_preview_lines_行数が増えスクロール位置は保たれる() {
+        // Arrange
+        let temp_dir = create_test_env();
+        let file = temp_dir.path().join("file.txt");
+        let content = (1..=30)
+            .map(|i| format!("Line {}\n", i))
+            .collect::<String>();
+        fs::write(&file, content).unwrap();
+        let mut app = App::new(vec![file], None);
+        wait_for_preview(&mut app);
+        app.preview_scroll = 3;
+
+        // Act
+        app.show_more_preview_lines();
+        wait_for_preview(&mut app);
+
+        // Assert
+        assert_eq!(app.preview_config().file_lines, 20);
+        assert_eq!(app.preview_scroll(), 3);
+        assert!(lines_to_string(app.preview()).contains("Line 20"));
+    }
+
+    /// ワークツリーを1つ持つリポジトリを作成する
+    fn create_repo_with_worktree() -> (TempDir, TempDir, PathBuf) {
+        let repo_dir = TempDir::new().unwrap();
+        let repo = crate::test_helper::setup_test_repo(&repo_dir);
+        let worktree_dir = TempDir::new().unwrap();
+        let worktree_path = worktree_dir.path().join("wt");
+        repo.worktree("wt", &worktree_path, None).unwrap();
+        (repo_dir, worktree_dir, worktree_path)
+    }
+
+    #[test]
+    fn test_expand_selected_ワークツリーが子の行として追加される() {
+        // Arrange
+        let (repo_dir, _worktree_dir, worktree_path) = create_repo_with_worktree();
+        let other = create_test_env();
+        let paths = vec![repo_dir.path().to_path_buf(), other.path().join("test1")];
+        let mut app = App::new(paths, None);
+
+        // Act
+        app.expand_selected();
+
+        // Assert
+        assert_eq!(app.paths().len(), 3);
+        assert_eq!(app.paths()[1], worktree_path);
+        assert_eq!(app.display_paths()[1], "  ↳ [worktree] wt");
+        assert!(app.is_expanded(0));
+        assert!(app.is_linked(1));
+        assert_eq!(app.selected(), 0);
+    }
+
+    #[test]
+    fn test_expand_selected_子の行を選択するとそのパスに移動できる() {
+        // Arrange
+        let (repo_dir, _worktree_dir, worktree_path) = create_repo_with_worktree();
+        let mut app = App::new(vec![repo_dir.path().to_path_buf()], None);
+        app.expand_selected();
+
+        // Act
+        app.select_next();
+        app.confirm_selection();
+
+        // Assert
+        assert_eq!(app.selected_path(), Some(&worktree_path));
+    }
+
+    #[test]
+    fn test_collapse_selected_子の行から親の展開を閉じる() {
+        // Arrange
+        let (repo_dir, _worktree_dir, _worktree_path) = create_repo_with_worktree();
+        let other = create_test_env();
+        let paths = vec![repo_dir.path().to_path_buf(), other.path().join("test1")];
+        let mut app = App::new(paths, None);
+        app.expand_selected();
+        app.select_next();
+
+        // Act
+        app.collapse_selected();
+
+        // Assert
+        assert_eq!(app.paths().len(), 2);
+        assert_eq!(app.paths()[1], other.path().join("test1"));
+        assert!(!app.is_expanded(0));
+        assert_eq!(app.selected(), 0);
+    }
+
+    #[test]
+    fn test_expand_selected_リポジトリ以外は展開しない() {
+        // Arrange
+        let temp_dir = create_test_env();
+        let mut app = App::new(vec![temp_dir.path().join("test1")], None);
+
+        // Act
+        app.expand_selected();
+
+        // Assert
+        assert_eq!(app.paths().len(), 1);
+        assert!(!app.is_expanded(0));
+    }
+
+    #[test]
+    fn test_badges_エントリの種類のバッジが取得される() {
+        // Arrange
+        let temp_dir = create_test_env();
+        let archive = temp_dir.path().join("release.zip");
+        std::fs::write(&archive, "").unwrap();
+
+        // Act
+        let mut app = App::new(vec![temp_dir.path().join("test1"), archive], None);
+        wait_for_attributes(&mut app);
+
+        // Assert
+        assert!(app.badges(0).is_empty());
+        assert_eq!(app.badges(1), ["zip".to_string()]);
+    }
+
+    #[test]
+    fn test_icon_設定でアイコンを無効にできる() {
+        // Arrange
+        let temp_dir = create_test_env();
+        let paths = vec![temp_dir.path().join("test1"), temp_dir.path().join("gone")];
+        let list_config = ListConfig {
+            icons: IconStyle::None,
+        };
+
+        // Act
+        let mut app = App::new(paths.clone(), None);
+        wait_for_attributes(&mut app);
+        let disabled = App::new(paths, None).with_list_config(&list_config);
+
+        // Assert
+        assert_eq!(app.icon(0).as_deref(), Some("📁 "));
+        assert_eq!(app.icon(1).as_deref(), Some("🚫 "));
+        assert_eq!(disabled.icon(0), None);
+    }
+
+    #[test]
+    fn test_path_state_存在しないパスはmissingになる() {
+        // Arrange
+        let temp_dir = create_test_env();
+        let paths = vec![temp_dir.path().join("test1"), temp_dir.path().join("gone")];
+
+        // Act
+        let mut app = App::new(paths, None);
+        wait_for_attributes(&mut app);
+
+        // Assert
+        assert_eq!(app.path_state(0), PathState::Available);
+        assert_eq!(app.path_state(1), PathState::Missing);
+    }
+
+    #[test]
+    fn test_new_判定が終わるまでは種類と状態が不明な行として表示する() {
+        // Arrange
+        let temp_dir = create_test_env();
+        let paths = vec![temp_dir.path().join("test1"), temp_dir.path().join("gone")];
+
+        // Act
+        let mut app = App::new(paths, None);
+        app.input = "kind:dir".to_string();
+        app.filter_paths();
+
+        // Assert
+        assert_eq!(app.icon(1).as_deref(), Some("   "));
+        assert_eq!(app.path_state(1), PathState::Available);
+        assert!(app.filtered_indices().is_empty());
+
+        wait_for_attributes(&mut app);
+        assert_eq!(app.icon(1).as_deref(), Some("🚫 "));
+        assert_eq!(app.filtered_indices(), &[(0, vec![])]);
+    }
+
     #[test]
     fn test_toggle_modal_persist_値がトグルされる() {
         // Arrange
@@ -889,7 +1645,7 @@ mod tests {
         app.cancel_modal();
 
         // Assert
-        assert_eq!(app.modal_persist_value(), false);
+        assert!(!app.modal_persist_value());
         assert_eq!(app.modal_tags_input(), "original");
     }
 
@@ -916,7 +1672,7 @@ mod tests {
 
         // Assert
         assert!(app.show_modal());
-        assert_eq!(app.modal_original_persist, true);
+        assert!(app.modal_original_persist);
         assert_eq!(app.modal_original_tags, "work, rust");
     }
 
@@ -925,7 +1681,7 @@ mod tests {
         // Arrange
         let temp_dir = create_test_env();
         let paths = vec![temp_dir.path().join("test1"), temp_dir.path().join("other")];
-        let mut app = App::new(paths, None);
+        let mut app = App::new(paths, None).with_search_config(&display_only());
 
         // Act
         app.input = "t1".to_string();
@@ -957,6 +1713,133 @@ mod tests {
         assert!(match_indices.is_empty());
     }
 
+    #[test]
+    fn test_filter_paths_拡張構文で絞り込まれる() {
+        // Arrange
+        let temp_dir = create_test_env();
+        let paths = vec![
+            temp_dir.path().join("test1"),
+            temp_dir.path().join("test2"),
+            temp_dir.path().join("other"),
+        ];
+        let mut app = App::new(paths, None).with_search_config(&display_only());
+
+        // Act
+        app.input = "^test !2".to_string();
+        app.filter_paths();
+
+        // Assert
+        assert_eq!(app.filtered_indices(), &[(0, vec![0, 1, 2, 3])]);
+    }
+
+    #[test]
+    fn test_filter_paths_タグとpersistの修飾子で絞り込まれる() {
+        // Arrange
+        let temp_dir = create_test_env();
+        let paths = vec![temp_dir.path().join("test1"), temp_dir.path().join("test2")];
+        let config_paths = vec![ConfigPath {
+            path: paths[1].clone(),
+            last_selected: chrono::Utc::now(),
+            persist: true,
+            tags: vec!["work".to_string()],
+            access_count: 0,
+        }];
+        let mut app = App::new(paths, None).with_config_paths(&config_paths);
+        wait_for_attributes(&mut app);
+
+        // Act & Assert
+        app.input = "#work".to_string();
+        app.filter_paths();
+        assert_eq!(app.filtered_indices(), &[(1, vec![])]);
+
+        app.input = "persist:no kind:dir".to_string();
+        app.filter_paths();
+        assert_eq!(app.filtered_indices(), &[(0, vec![])]);
+    }
+
+    #[test]
+    fn test_filter_paths_スコープに絶対パスを含めると親ディレクトリ名で検索できる() {
+        // Arrange
+        let temp_dir = create_test_env();
+        let paths = vec![temp_dir.path().join("test1"), temp_dir.path().join("test2")];
+        let parent_name = temp_dir
+            .path()
+            .file_name()
+            .unwrap()
+            .to_string_lossy()
+            .into_owned();
+        let search_config = SearchConfig {
+            scope: vec![SearchField::Display, SearchField::Path],
+            ..SearchConfig::default()
+        };
+        let mut app = App::new(paths.clone(), None).with_search_config(&display_only());
+
+        // Act & Assert
+        app.input = format!("'{}", parent_name);
+        app.filter_paths();
+        assert!(app.filtered_indices().is_empty());
+
+        let mut app = App::new(paths, None).with_search_config(&search_config);
+        app.input = format!("'{}", parent_name);
+        app.filter_paths();
+        assert_eq!(app.filtered_indices().len(), 2);
+    }
+
+    #[test]
+    fn test_cycle_matcher_algorithm_アルゴリズムが切り替わり再フィルタリングされる() {
+        // Arrange
+        let temp_dir = create_test_env();
+        let paths = vec![temp_dir.path().join("test1"), temp_dir.path().join("test2")];
+        let mut app = App::new(paths, None).with_search_config(&display_only());
+        app.input = "tt1".to_string();
+        app.filter_paths();
+        assert_eq!(app.filtered_indices().len(), 1);
+
+        // Act
+        app.cycle_matcher_algorithm(); // clangd
+        app.cycle_matcher_algorithm(); // exact
+
+        // Assert
+        assert_eq!(app.matcher().algorithm(), MatcherAlgorithm::Exact);
+        assert!(app.filtered_indices().is_empty());
+    }
+
+    #[test]
+    fn test_cycle_case_matching_大文字小文字の扱いが切り替わる() {
+        // Arrange
+        let temp_dir = create_test_env();
+        let paths = vec![temp_dir.path().join("test1")];
+        let mut app = App::new(paths, None).with_search_config(&display_only());
+        app.input = "TEST".to_string();
+        app.filter_paths();
+        assert!(app.filtered_indices().is_empty());
+
+        // Act
+        app.cycle_case_matching(); // respect
+        app.cycle_case_matching(); // ignore
+
+        // Assert
+        assert_eq!(app.matcher().case(), CaseMatching::Ignore);
+        assert_eq!(app.filtered_indices().len(), 1);
+    }
+
+    #[test]
+    fn test_confirm_modal_検索用のタグも更新される() {
+        // Arrange
+        let temp_dir = create_test_env();
+        let paths = vec![temp_dir.path().join("test1")];
+        let mut app = App::new(paths, None);
+        app.modal_tags_input = "rust".to_string();
+
+        // Act
+        app.confirm_modal();
+        app.input = "#rust".to_string();
+        app.filter_paths();
+
+        // Assert
+        assert_eq!(app.filtered_indices().len(), 1);
+    }
+
     #[test]
     fn test_select_next_空のリストでは何もしない() {
         // Arrange
@@ -0,0 +1,150 @@
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+
+use crate::entry::registry::registry;
+use crate::entry::state::PathState;
+use crate::entry::EntryKind;
+
+/// ファイルシステムを調べて判定するパスの属性
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DetectedAttributes {
+    /// エントリの種類
+    pub kind: EntryKind,
+    /// パスを開けるかどうか
+    pub state: PathState,
+    /// 一覧に表示するエントリの種類のバッジ
+    pub badges: Vec<String>,
+}
+
+impl DetectedAttributes {
+    /// パスの状態・種類・バッジを判定する
+    ///
+    /// エントリは作成せず、レジストリの判定とバッジの取得だけを行う
+    /// （開けないパスは種類を判定せずファイルとする）
+    pub fn detect(path: &Path) -> Self {
+        let state = PathState::detect(path);
+        let registration = state
+            .is_available()
+            .then(|| registry().detect(path).copied())
+            .flatten();
+        let (kind, badges) = match registration {
+            Some(registration) => (registration.kind, (registration.badges)(path)),
+            None => (EntryKind::FILE, Vec::new()),
+        };
+        Self {
+            kind,
+            state,
+            badges,
+        }
+    }
+}
+
+/// パスの属性をバックグラウンドで判定する
+///
+/// マウントされていないボリュームやリポジトリの判定で描画が止まらないよう、
+/// 判定はワーカースレッドでリクエストされた順に行う。
+/// ワーカースレッドはAttributeLoaderが破棄されると終了する
+pub struct AttributeLoader {
+    request_tx: Sender<PathBuf>,
+    response_rx: Receiver<(PathBuf, DetectedAttributes)>,
+    /// 結果待ちのリクエストの数
+    pending: usize,
+}
+
+impl Default for AttributeLoader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AttributeLoader {
+    /// ワーカースレッドを起動してAttributeLoaderを作成する
+    pub fn new() -> Self {
+        let (request_tx, request_rx) = mpsc::channel::<PathBuf>();
+        let (response_tx, response_rx) = mpsc::channel();
+
+        thread::spawn(move || {
+            while let Ok(path) = request_rx.recv() {
+                let attributes = DetectedAttributes::detect(&path);
+                if response_tx.send((path, attributes)).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Self {
+            request_tx,
+            response_rx,
+            pending: 0,
+        }
+    }
+
+    /// パスの属性の判定をリクエストする
+    pub fn request(&mut self, path: PathBuf) {
+        if self.request_tx.send(path).is_ok() {
+            self.pending += 1;
+        }
+    }
+
+    /// 判定が終わった属性を取り出す
+    pub fn poll(&mut self) -> Vec<(PathBuf, DetectedAttributes)> {
+        let detected: Vec<_> = self.response_rx.try_iter().collect();
+        self.pending = self.pending.saturating_sub(detected.len());
+        detected
+    }
+
+    /// 判定中のパスがあるかどうか
+    pub fn is_pending(&self) -> bool {
+        self.pending > 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn test_poll_リクエストしたパスの属性が判定される() {
+        // Arrange
+        let temp_dir = tempfile::tempdir().unwrap();
+        let archive = temp_dir.path().join("release.zip");
+        std::fs::write(&archive, "").unwrap();
+        let missing = temp_dir.path().join("gone");
+        let mut loader = AttributeLoader::new();
+
+        // Act
+        loader.request(archive.clone());
+        loader.request(missing.clone());
+        let mut detected = Vec::new();
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while loader.is_pending() && Instant::now() < deadline {
+            detected.extend(loader.poll());
+            thread::sleep(Duration::from_millis(5));
+        }
+
+        // Assert
+        assert_eq!(
+            detected,
+            vec![
+                (
+                    archive,
+                    DetectedAttributes {
+                        kind: EntryKind::ARCHIVE,
+                        state: PathState::Available,
+                        badges: vec!["zip".to_string()],
+                    }
+                ),
+                (
+                    missing,
+                    DetectedAttributes {
+                        kind: EntryKind::FILE,
+                        state: PathState::Missing,
+                        badges: Vec::new(),
+                    }
+                ),
+            ]
+        );
+    }
+}
@@ -3,6 +3,7 @@ use crossterm::event::{self, Event, KeyCode, KeyModifiers};
 
 use super::app::App;
 use super::focus::{FocusedPanel, ModalFocus};
+use super::keymap::{Action, KeyChord, KeyLookup, KeyScope};
 use crate::Pavo;
 
 /// TUIのイベントハンドリング
@@ -11,7 +12,13 @@ use crate::Pavo;
 /// * `app` - アプリケーションの状態
 /// * `pavo` - Pavoインスタンス
 pub fn handle_event(app: &mut App, pavo: &mut Pavo) -> Result<()> {
-    if event::poll(std::time::Duration::from_millis(100))? {
+    // プレビューやパスの属性の判定中は結果をすぐ反映できるよう短い間隔でポーリングする
+    let timeout = if app.is_preview_loading() || app.is_detecting_attributes() {
+        std::time::Duration::from_millis(10)
+    } else {
+        std::time::Duration::from_millis(100)
+    };
+    if event::poll(timeout)? {
         if let Event::Key(key) = event::read()? {
             // モーダルが開いている場合の処理
             if app.show_modal() {
@@ -31,48 +38,55 @@ fn handle_modal_event(
     app: &mut App,
     pavo: &mut Pavo,
     key_code: KeyCode,
-    _key_modifiers: KeyModifiers,
+    key_modifiers: KeyModifiers,
 ) -> Result<()> {
-    match key_code {
-        KeyCode::Enter => {
+    let chord = KeyChord::new(key_code, key_modifiers);
+    match app.keymap().action(KeyScope::Modal, &[chord]) {
+        KeyLookup::Action(Action::Save) => {
             if let Some((idx, new_persist, new_tags)) = app.confirm_modal() {
                 let path = &app.paths()[idx];
                 pavo.set_persist(path, new_persist)?;
                 pavo.set_tags(path, new_tags)?;
             }
             app.close_modal();
+            return Ok(());
         }
-        KeyCode::Esc => {
+        KeyLookup::Action(Action::Cancel) => {
             app.cancel_modal();
             app.close_modal();
+            return Ok(());
         }
-        KeyCode::Tab => {
+        KeyLookup::Action(Action::NextField) => {
             app.modal_focus_next();
+            return Ok(());
         }
-        KeyCode::Up | KeyCode::Down | KeyCode::Char(' ') => {
-            if app.modal_focus() == ModalFocus::Persist {
-                app.toggle_modal_persist();
-            }
+        KeyLookup::Action(Action::TogglePersist) if app.modal_focus() == ModalFocus::Persist => {
+            app.toggle_modal_persist();
+            return Ok(());
         }
+        _ => {}
+    }
+
+    // タグ入力の編集
+    if app.modal_focus() != ModalFocus::Tags {
+        return Ok(());
+    }
+    match key_code {
+        // タグ入力ではスペースを受け付けない
+        KeyCode::Char(' ') => {}
         KeyCode::Backspace => {
-            if app.modal_focus() == ModalFocus::Tags {
-                app.delete_char_from_modal_tags();
-            }
+            app.delete_char_from_modal_tags();
         }
         KeyCode::Left => {
-            if app.modal_focus() == ModalFocus::Tags {
-                app.move_modal_cursor_left();
-            }
+            app.move_modal_cursor_left();
         }
         KeyCode::Right => {
-            if app.modal_focus() == ModalFocus::Tags {
-                app.move_modal_cursor_right();
-            }
+            app.move_modal_cursor_right();
         }
-        KeyCode::Char(c) => {
-            if app.modal_focus() == ModalFocus::Tags {
-                app.add_char_to_modal_tags(c);
-            }
+        KeyCode::Char(c)
+            if !key_modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) =>
+        {
+            app.add_char_to_modal_tags(c);
         }
         _ => {}
     }
@@ -81,72 +95,81 @@ fn handle_modal_event(
 
 /// 通常のイベント処理
 fn handle_normal_event(app: &mut App, pavo: &Pavo, key_code: KeyCode, key_modifiers: KeyModifiers) {
-    match (key_code, key_modifiers) {
-        (KeyCode::Char('c'), KeyModifiers::CONTROL) | (KeyCode::Esc, _) => {
-            app.quit();
-        }
-        (KeyCode::Tab, KeyModifiers::NONE) => {
-            app.focus_next_panel();
-        }
-        (KeyCode::BackTab, _) => {
-            app.focus_previous_panel();
-        }
-        (KeyCode::Enter, _) => match app.focused_panel() {
-            FocusedPanel::Search => {
-                app.confirm_selection();
-            }
-            FocusedPanel::Paths => {
-                app.open_modal(pavo);
-            }
-            FocusedPanel::Preview => {}
-        },
-        (KeyCode::Down, _) | (KeyCode::Char('n'), KeyModifiers::CONTROL) => {
-            match app.focused_panel() {
-                FocusedPanel::Search | FocusedPanel::Paths => {
-                    app.select_next();
-                }
-                FocusedPanel::Preview => {
-                    app.scroll_preview_down();
-                }
-            }
-        }
-        (KeyCode::Up, _) | (KeyCode::Char('p'), KeyModifiers::CONTROL) => {
-            match app.focused_panel() {
-                FocusedPanel::Search | FocusedPanel::Paths => {
-                    app.select_previous();
-                }
-                FocusedPanel::Preview => {
-                    app.scroll_preview_up();
+    let chord = KeyChord::new(key_code, key_modifiers);
+    let scope = KeyScope::from(app.focused_panel());
+    let mut sequence = app.take_pending_keys();
+    sequence.push(chord);
+    let mut lookup = app.keymap().action(scope, &sequence);
+    // 入力途中の並びの続きとして一致しない場合は、最後のキーだけで探し直す
+    if lookup == KeyLookup::Unbound && sequence.len() > 1 {
+        sequence = vec![chord];
+        lookup = app.keymap().action(scope, &sequence);
+    }
+
+    match lookup {
+        KeyLookup::Action(action) => perform_action(app, pavo, action),
+        KeyLookup::Pending => app.set_pending_keys(sequence),
+        // 割り当てのない文字キーはSearchパネルでのみ入力として扱う
+        KeyLookup::Unbound => {
+            if let (KeyCode::Char(c), KeyModifiers::NONE | KeyModifiers::SHIFT) =
+                (key_code, key_modifiers)
+            {
+                if app.focused_panel() == FocusedPanel::Search {
+                    app.add_char(c);
                 }
             }
         }
-        (KeyCode::Backspace, _) => {
-            if app.focused_panel() == FocusedPanel::Search {
-                app.delete_char();
-            }
-        }
-        (KeyCode::Left, _) => {
-            if app.focused_panel() == FocusedPanel::Search {
-                app.move_cursor_left();
-            }
-        }
-        (KeyCode::Right, _) => {
-            if app.focused_panel() == FocusedPanel::Search {
-                app.move_cursor_right();
-            }
-        }
-        (KeyCode::Char(c), KeyModifiers::NONE | KeyModifiers::SHIFT) => {
-            if app.focused_panel() == FocusedPanel::Search {
-                app.add_char(c);
-            }
-        }
-        _ => {}
+    }
+}
+
+/// パネルの操作を実行する
+fn perform_action(app: &mut App, pavo: &Pavo, action: Action) {
+    match action {
+        Action::Quit => app.quit(),
+        Action::FocusNext => app.focus_next_panel(),
+        Action::FocusPrevious => app.focus_previous_panel(),
+        Action::CycleAlgorithm => app.cycle_matcher_algorithm(),
+        Action::CycleCase => app.cycle_case_matching(),
+        Action::FocusSearch => app.focus_panel(FocusedPanel::Search),
+        Action::FocusPaths => app.focus_panel(FocusedPanel::Paths),
+        Action::Confirm => app.confirm_selection(),
+        Action::Edit => app.open_modal(pavo),
+        Action::SelectNext => app.select_next(),
+        Action::SelectPrevious => app.select_previous(),
+        Action::SelectFirst => app.select_first(),
+        Action::SelectLast => app.select_last(),
+        Action::SelectHalfPageDown => app.select_half_page_down(),
+        Action::SelectHalfPageUp => app.select_half_page_up(),
+        Action::Expand => app.expand_selected(),
+        Action::Collapse => app.collapse_selected(),
+        Action::DeleteChar => app.delete_char(),
+        Action::CursorLeft => app.move_cursor_left(),
+        Action::CursorRight => app.move_cursor_right(),
+        Action::ScrollDown => app.scroll_preview_down(),
+        Action::ScrollUp => app.scroll_preview_up(),
+        Action::ScrollTop => app.scroll_preview_top(),
+        Action::ScrollBottom => app.scroll_preview_bottom(),
+        Action::ScrollHalfPageDown => app.scroll_preview_half_page_down(),
+        Action::ScrollHalfPageUp => app.scroll_preview_half_page_up(),
+        Action::IncreaseDepth => app.increase_preview_depth(),
+        Action::DecreaseDepth => app.decrease_preview_depth(),
+        Action::ToggleHidden => app.toggle_preview_hidden(),
+        Action::ToggleWrap => app.toggle_preview_wrap(),
+        Action::ShowMore => app.show_more_preview_lines(),
+        // モーダルの操作と割り当ての解除はパネルでは使われない
+        Action::Save
+        | Action::Cancel
+        | Action::NextField
+        | Action::TogglePersist
+        | Action::Unbound => {}
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::{KeyMode, KeysConfig};
+    use crate::tui::keymap::Keymap;
     use tempfile::TempDir;
 
     fn create_test_env() -> TempDir {
@@ -298,6 +321,37 @@ mod tests {
         assert_eq!(app.selected(), 1);
     }
 
+    #[test]
+    fn test_handle_normal_event_ctrl_t_でマッチアルゴリズムを切り替える() {
+        // Arrange
+        let temp_dir = create_test_env();
+        let paths = vec![temp_dir.path().join("test1")];
+        let mut app = App::new(paths, None);
+        let (pavo, _config_dir) = create_test_pavo();
+
+        // Act
+        handle_normal_event(&mut app, &pavo, KeyCode::Char('t'), KeyModifiers::CONTROL);
+
+        // Assert
+        assert_eq!(app.matcher().algorithm().name(), "clangd");
+        assert_eq!(app.input(), "");
+    }
+
+    #[test]
+    fn test_handle_normal_event_ctrl_s_で大文字小文字の扱いを切り替える() {
+        // Arrange
+        let temp_dir = create_test_env();
+        let paths = vec![temp_dir.path().join("test1")];
+        let mut app = App::new(paths, None);
+        let (pavo, _config_dir) = create_test_pavo();
+
+        // Act
+        handle_normal_event(&mut app, &pavo, KeyCode::Char('s'), KeyModifiers::CONTROL);
+
+        // Assert
+        assert_eq!(app.matcher().case().name(), "respect-case");
+    }
+
     #[test]
     fn test_handle_normal_event_backspace_search_パネルで文字削除() {
         // Arrange
@@ -389,6 +443,37 @@ mod tests {
         assert!(!app.show_modal());
     }
 
+    #[test]
+    fn test_handle_modal_event_ワークツリーの子の行は編集も保存もしない() {
+        // Arrange
+        let repo_dir = tempfile::tempdir().unwrap();
+        let repo = crate::test_helper::setup_test_repo(&repo_dir);
+        let worktree_dir = tempfile::tempdir().unwrap();
+        repo.worktree("wt", &worktree_dir.path().join("wt"), None)
+            .unwrap();
+        let repo_path = repo_dir.path().canonicalize().unwrap();
+        let (mut pavo, _config_dir) = create_test_pavo();
+        pavo.add_path(repo_path.to_str().unwrap(), false).unwrap();
+        let mut app = App::new(vec![repo_path], None);
+        app.expand_selected();
+        app.select_next();
+
+        // Act
+        handle_normal_event(&mut app, &pavo, KeyCode::Enter, KeyModifiers::NONE);
+        let opened = app.show_modal();
+        app.set_show_modal(true);
+        app.modal_focus_next(); // Tags フィールドに移動
+        app.add_char_to_modal_tags('x');
+        let result = handle_modal_event(&mut app, &mut pavo, KeyCode::Enter, KeyModifiers::NONE);
+
+        // Assert
+        assert!(!opened);
+        assert!(result.is_ok());
+        assert!(!app.show_modal());
+        assert_eq!(pavo.get_paths().len(), 1);
+        assert!(pavo.get_paths()[0].tags.is_empty());
+    }
+
     #[test]
     fn test_handle_modal_event_esc_でモーダルキャンセル() {
         // Arrange
@@ -514,4 +599,159 @@ mod tests {
         // Assert
         assert_eq!(app.input(), ""); // 変更されない
     }
+
+    #[test]
+    fn test_handle_normal_event_previewパネルでプレビューの設定を変更できる() {
+        // Arrange
+        let temp_dir = create_test_env();
+        let paths = vec![temp_dir.path().join("test1")];
+        let mut app = App::new(paths, None);
+        let (pavo, _config_dir) = create_test_pavo();
+        app.focus_next_panel();
+        app.focus_next_panel(); // Preview パネルに移動
+
+        // Act
+        handle_normal_event(&mut app, &pavo, KeyCode::Char('+'), KeyModifiers::SHIFT);
+        handle_normal_event(&mut app, &pavo, KeyCode::Char('.'), KeyModifiers::NONE);
+        handle_normal_event(&mut app, &pavo, KeyCode::Char('w'), KeyModifiers::NONE);
+        handle_normal_event(&mut app, &pavo, KeyCode::Char('m'), KeyModifiers::NONE);
+
+        // Assert
+        assert_eq!(app.preview_config().tree_depth, 2);
+        assert!(app.preview_config().show_hidden);
+        assert!(!app.preview_wrap());
+        assert_eq!(app.preview_config().file_lines, 20);
+        assert_eq!(app.input(), "");
+    }
+
+    #[test]
+    fn test_handle_normal_event_pathsパネルで左右キーで展開と折りたたみ() {
+        // Arrange
+        let repo_dir = tempfile::tempdir().unwrap();
+        let repo = crate::test_helper::setup_test_repo(&repo_dir);
+        let worktree_dir = tempfile::tempdir().unwrap();
+        repo.worktree("wt", &worktree_dir.path().join("wt"), None)
+            .unwrap();
+        let mut app = App::new(vec![repo_dir.path().to_path_buf()], None);
+        let (pavo, _config_dir) = create_test_pavo();
+        app.focus_next_panel(); // Paths パネルに移動
+
+        // Act & Assert
+        handle_normal_event(&mut app, &pavo, KeyCode::Right, KeyModifiers::NONE);
+        assert_eq!(app.paths().len(), 2);
+        handle_normal_event(&mut app, &pavo, KeyCode::Left, KeyModifiers::NONE);
+        assert_eq!(app.paths().len(), 1);
+    }
+
+    #[test]
+    fn test_handle_normal_event_searchパネルでは文字として入力される() {
+        // Arrange
+        let temp_dir = create_test_env();
+        let paths = vec![temp_dir.path().join("test1")];
+        let mut app = App::new(paths, None);
+        let (pavo, _config_dir) = create_test_pavo();
+
+        // Act
+        handle_normal_event(&mut app, &pavo, KeyCode::Char('-'), KeyModifiers::NONE);
+
+        // Assert
+        assert_eq!(app.preview_config().tree_depth, 1);
+        assert_eq!(app.input(), "-");
+    }
+
+    #[test]
+    fn test_handle_normal_event_設定したキーの割り当てで操作する() {
+        // Arrange
+        let temp_dir = create_test_env();
+        let paths = vec![temp_dir.path().join("test1"), temp_dir.path().join("test2")];
+        let keys = KeysConfig {
+            paths: std::collections::BTreeMap::from([
+                ("j".to_string(), "select_next".to_string()),
+                ("down".to_string(), "none".to_string()),
+            ]),
+            ..KeysConfig::default()
+        };
+        let mut app = App::new(paths, None).with_keymap(Keymap::new(&keys).unwrap());
+        let (pavo, _config_dir) = create_test_pavo();
+        app.focus_next_panel(); // Paths パネルに移動
+
+        // Act & Assert
+        handle_normal_event(&mut app, &pavo, KeyCode::Down, KeyModifiers::NONE);
+        assert_eq!(app.selected(), 0);
+        handle_normal_event(&mut app, &pavo, KeyCode::Char('j'), KeyModifiers::NONE);
+        assert_eq!(app.selected(), 1);
+    }
+
+    #[test]
+    fn test_handle_normal_event_vim方式で挿入モードとノーマルモードを切り替えて移動する() {
+        // Arrange
+        let temp_dir = create_test_env();
+        std::fs::create_dir_all(temp_dir.path().join("test3")).unwrap();
+        let paths = vec![
+            temp_dir.path().join("test1"),
+            temp_dir.path().join("test2"),
+            temp_dir.path().join("test3"),
+        ];
+        let keys = KeysConfig {
+            mode: KeyMode::Vim,
+            ..KeysConfig::default()
+        };
+        let mut app = App::new(paths, None).with_keymap(Keymap::new(&keys).unwrap());
+        let (pavo, _config_dir) = create_test_pavo();
+        let press = |app: &mut App, code: KeyCode| {
+            handle_normal_event(app, &pavo, code, KeyModifiers::NONE);
+        };
+
+        // Act & Assert
+        press(&mut app, KeyCode::Char('j'));
+        assert_eq!(app.input(), "j"); // 挿入モードでは文字として入力される
+        press(&mut app, KeyCode::Backspace);
+        press(&mut app, KeyCode::Esc);
+        assert_eq!(app.focused_panel(), FocusedPanel::Paths);
+        assert!(!app.should_quit());
+        press(&mut app, KeyCode::Char('j'));
+        assert_eq!(app.selected(), 1);
+        press(&mut app, KeyCode::Char('G'));
+        assert_eq!(app.selected(), 2);
+        press(&mut app, KeyCode::Char('g'));
+        assert_eq!(app.selected(), 2); // 続きのキーを待つ
+        press(&mut app, KeyCode::Char('g'));
+        assert_eq!(app.selected(), 0);
+        press(&mut app, KeyCode::Char('/'));
+        assert_eq!(app.focused_panel(), FocusedPanel::Search);
+        assert_eq!(app.input(), "");
+        press(&mut app, KeyCode::Esc);
+        press(&mut app, KeyCode::Esc);
+        assert!(app.should_quit());
+    }
+
+    #[test]
+    fn test_handle_normal_event_vim方式のctrl_dとctrl_uで半ページ移動する() {
+        // Arrange
+        let temp_dir = tempfile::tempdir().unwrap();
+        let paths: Vec<_> = (0..10)
+            .map(|i| {
+                let path = temp_dir.path().join(format!("dir{}", i));
+                std::fs::create_dir_all(&path).unwrap();
+                path
+            })
+            .collect();
+        let keys = KeysConfig {
+            mode: KeyMode::Vim,
+            ..KeysConfig::default()
+        };
+        let mut app = App::new(paths, None).with_keymap(Keymap::new(&keys).unwrap());
+        let (pavo, _config_dir) = create_test_pavo();
+        app.set_page_height(8);
+        app.focus_next_panel(); // Paths パネルに移動
+
+        // Act & Assert
+        handle_normal_event(&mut app, &pavo, KeyCode::Char('d'), KeyModifiers::CONTROL);
+        assert_eq!(app.selected(), 4);
+        handle_normal_event(&mut app, &pavo, KeyCode::Char('d'), KeyModifiers::CONTROL);
+        handle_normal_event(&mut app, &pavo, KeyCode::Char('d'), KeyModifiers::CONTROL);
+        assert_eq!(app.selected(), 9); // 末尾で止まる
+        handle_normal_event(&mut app, &pavo, KeyCode::Char('u'), KeyModifiers::CONTROL);
+        assert_eq!(app.selected(), 5);
+    }
 }
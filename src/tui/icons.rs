@@ -0,0 +1,216 @@
+use std::path::Path;
+use unicode_width::UnicodeWidthStr;
+
+use crate::config::IconStyle;
+use crate::entry::file::file_icon;
+use crate::entry::registry::registry;
+use crate::entry::state::PathState;
+use crate::entry::EntryKind;
+
+/// アイコンを表示する列の幅（全角の絵文字に合わせる）
+const ICON_WIDTH: usize = 2;
+
+/// 開けないパスのNerd Fontのグリフ
+const NERDFONT_UNAVAILABLE: &str = "\u{f05e}";
+
+/// 種類ごとのNerd Fontのグリフ
+const NERDFONT_KINDS: &[(EntryKind, &str)] = &[
+    (EntryKind::DIRECTORY, "\u{f07b}"),
+    (EntryKind::REPOSITORY, "\u{e702}"),
+    (EntryKind::ARCHIVE, "\u{f410}"),
+];
+
+/// 拡張子に対応するグリフがないファイルのNerd Fontのグリフ
+const NERDFONT_FILE: &str = "\u{f15b}";
+
+/// 拡張子ごとのファイルのNerd Fontのグリフ
+const NERDFONT_FILES: &[(&[&str], &str)] = &[
+    (
+        &["zip", "tar", "gz", "tgz", "xz", "bz2", "zst", "7z"],
+        "\u{f410}",
+    ),
+    (&["rs"], "\u{e7a8}"),
+    (&["py"], "\u{e606}"),
+    (&["md", "rst", "txt"], "\u{f48a}"),
+    (&["toml", "json", "yaml", "yml", "ini", "conf"], "\u{e615}"),
+    (&["sh", "bash", "zsh", "fish"], "\u{f489}"),
+    (&["png", "jpg", "jpeg", "gif", "svg", "webp"], "\u{f1c5}"),
+    (&["pdf"], "\u{f1c1}"),
+];
+
+/// パスの種類と状態に応じたアイコンを取得する
+///
+/// Unicodeの絵文字はレジストリに登録された種類のアイコン（`Entry::icon`と同じもの）を使う。
+/// 表示幅を揃えるため、アイコンの後ろを空白で埋めた文字列を返す
+/// （`IconStyle::None`の場合は`None`を返す）
+pub fn icon(style: IconStyle, path: &Path, kind: EntryKind, state: PathState) -> Option<String> {
+    let glyph = match style {
+        IconStyle::Unicode => unicode_icon(path, kind, state),
+        IconStyle::Nerdfont => nerdfont_icon(path, kind, state),
+        IconStyle::None => return None,
+    };
+    let padding = ICON_WIDTH.saturating_sub(glyph.width()) + 1;
+    Some(format!("{}{}", glyph, " ".repeat(padding)))
+}
+
+/// 種類の判定が終わっていないパスに表示する、アイコンと同じ幅の空白
+pub fn placeholder(style: IconStyle) -> Option<String> {
+    (style != IconStyle::None).then(|| " ".repeat(ICON_WIDTH + 1))
+}
+
+fn unicode_icon(path: &Path, kind: EntryKind, state: PathState) -> &'static str {
+    if !state.is_available() {
+        return PathState::UNAVAILABLE_ICON;
+    }
+    registry()
+        .find(kind.name())
+        .map_or_else(|| file_icon(path), |registration| (registration.icon)(path))
+}
+
+/// Nerd Fontのグリフ（対応するグリフがない種類は絵文字を使う）
+fn nerdfont_icon(path: &Path, kind: EntryKind, state: PathState) -> &'static str {
+    if !state.is_available() {
+        return NERDFONT_UNAVAILABLE;
+    }
+    if kind == EntryKind::FILE {
+        return nerdfont_file_icon(path);
+    }
+    NERDFONT_KINDS
+        .iter()
+        .find(|(nerdfont_kind, _)| *nerdfont_kind == kind)
+        .map_or_else(|| unicode_icon(path, kind, state), |(_, glyph)| glyph)
+}
+
+/// 拡張子からファイルのNerd Fontのグリフを選ぶ
+fn nerdfont_file_icon(path: &Path) -> &'static str {
+    let extension = path
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_lowercase());
+    extension
+        .and_then(|extension| {
+            NERDFONT_FILES
+                .iter()
+                .find(|(extensions, _)| extensions.contains(&extension.as_str()))
+        })
+        .map_or(NERDFONT_FILE, |(_, glyph)| glyph)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_icon_種類と拡張子に応じたアイコンを返す() {
+        // Arrange
+        let style = IconStyle::Unicode;
+
+        // Act
+        let dir = icon(
+            style,
+            Path::new("/src"),
+            EntryKind::DIRECTORY,
+            PathState::Available,
+        );
+        let repo = icon(
+            style,
+            Path::new("/pavo"),
+            EntryKind::REPOSITORY,
+            PathState::Available,
+        );
+        let rust = icon(
+            style,
+            Path::new("main.rs"),
+            EntryKind::FILE,
+            PathState::Available,
+        );
+        let archive = icon(
+            style,
+            Path::new("a.TGZ"),
+            EntryKind::ARCHIVE,
+            PathState::Available,
+        );
+        let other = icon(
+            style,
+            Path::new("a.unknown"),
+            EntryKind::FILE,
+            PathState::Available,
+        );
+        let missing = icon(
+            style,
+            Path::new("/gone"),
+            EntryKind::FILE,
+            PathState::Missing,
+        );
+
+        // Assert
+        assert_eq!(dir.as_deref(), Some("📁 "));
+        assert_eq!(repo.as_deref(), Some("🌿 "));
+        assert_eq!(rust.as_deref(), Some("🦀 "));
+        assert_eq!(archive.as_deref(), Some("📦 "));
+        assert_eq!(other.as_deref(), Some("📄 "));
+        assert_eq!(missing.as_deref(), Some("🚫 "));
+    }
+
+    #[test]
+    fn test_icon_絵文字はエントリのアイコンと一致する() {
+        // Arrange
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("main.py");
+        std::fs::write(&source, "").unwrap();
+
+        // Act & Assert
+        for path in [dir.path(), source.as_path()] {
+            let registration = *registry().detect(path).unwrap();
+            let entry = (registration.create)(path.to_path_buf(), &Default::default());
+            let expected = format!("{} ", entry.icon());
+            let actual = icon(
+                IconStyle::Unicode,
+                path,
+                registration.kind,
+                PathState::Available,
+            );
+            assert_eq!(
+                actual.as_deref().map(str::trim_end),
+                Some(expected.trim_end())
+            );
+        }
+    }
+
+    #[test]
+    fn test_icon_表示幅が揃うように空白で埋める() {
+        // Arrange
+        let path = Path::new("main.rs");
+
+        // Act
+        let unicode = icon(
+            IconStyle::Unicode,
+            path,
+            EntryKind::FILE,
+            PathState::Available,
+        );
+        let nerdfont = icon(
+            IconStyle::Nerdfont,
+            path,
+            EntryKind::FILE,
+            PathState::Available,
+        );
+
+        // Assert
+        assert_eq!(unicode.unwrap().width(), ICON_WIDTH + 1);
+        assert_eq!(nerdfont.unwrap().width(), ICON_WIDTH + 1);
+    }
+
+    #[test]
+    fn test_icon_noneの場合はアイコンを表示しない() {
+        // Act
+        let icon = icon(
+            IconStyle::None,
+            Path::new("/src"),
+            EntryKind::DIRECTORY,
+            PathState::Available,
+        );
+
+        // Assert
+        assert_eq!(icon, None);
+    }
+}
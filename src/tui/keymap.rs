@@ -0,0 +1,947 @@
+use anyhow::{anyhow, bail, Result};
+use crossterm::event::{KeyCode, KeyModifiers};
+use serde::de::{value, IntoDeserializer};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+use super::focus::FocusedPanel;
+use crate::config::{KeyMode, KeysConfig};
+
+/// キーに割り当てる操作
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    /// 終了する
+    Quit,
+    /// 次のパネルにフォーカスを移動する
+    FocusNext,
+    /// 前のパネルにフォーカスを移動する
+    FocusPrevious,
+    /// マッチアルゴリズムを切り替える
+    CycleAlgorithm,
+    /// 大文字小文字の扱いを切り替える
+    CycleCase,
+    /// Searchパネルにフォーカスを移動する
+    FocusSearch,
+    /// Pathsパネルにフォーカスを移動する
+    FocusPaths,
+    /// 選択中のパスを確定して終了する
+    Confirm,
+    /// 選択中のパスの設定モーダルを開く
+    Edit,
+    /// 次のパスを選択する
+    SelectNext,
+    /// 前のパスを選択する
+    SelectPrevious,
+    /// 最初のパスを選択する
+    SelectFirst,
+    /// 最後のパスを選択する
+    SelectLast,
+    /// 半ページ下のパスを選択する
+    SelectHalfPageDown,
+    /// 半ページ上のパスを選択する
+    SelectHalfPageUp,
+    /// ワークツリーとサブモジュールを展開する
+    Expand,
+    /// ワークツリーとサブモジュールを折りたたむ
+    Collapse,
+    /// 検索の入力欄の文字を削除する
+    DeleteChar,
+    /// 検索の入力欄のカーソルを左に移動する
+    CursorLeft,
+    /// 検索の入力欄のカーソルを右に移動する
+    CursorRight,
+    /// プレビューを下にスクロールする
+    ScrollDown,
+    /// プレビューを上にスクロールする
+    ScrollUp,
+    /// プレビューの先頭までスクロールする
+    ScrollTop,
+    /// プレビューの末尾までスクロールする
+    ScrollBottom,
+    /// プレビューを半ページ下にスクロールする
+    ScrollHalfPageDown,
+    /// プレビューを半ページ上にスクロールする
+    ScrollHalfPageUp,
+    /// ディレクトリツリーの深さを増やす
+    IncreaseDepth,
+    /// ディレクトリツリーの深さを減らす
+    DecreaseDepth,
+    /// ドットファイルの表示を切り替える
+    ToggleHidden,
+    /// プレビューの折り返しを切り替える
+    ToggleWrap,
+    /// ファイルのプレビューの行数を増やす
+    ShowMore,
+    /// モーダルの変更を保存して閉じる
+    Save,
+    /// モーダルの変更を破棄して閉じる
+    Cancel,
+    /// モーダルの次のフィールドにフォーカスを移動する
+    NextField,
+    /// モーダルのpersist値を切り替える
+    TogglePersist,
+    /// キーの割り当てを解除する
+    #[serde(rename = "none")]
+    Unbound,
+}
+
+impl FromStr for Action {
+    type Err = anyhow::Error;
+
+    /// 設定ファイルで使う操作の名前（`scroll_down`など）から操作を取得する
+    fn from_str(name: &str) -> Result<Self> {
+        Self::deserialize(IntoDeserializer::<value::Error>::into_deserializer(name))
+            .map_err(|_| anyhow!("Unknown action `{}`", name))
+    }
+}
+
+impl Action {
+    /// 操作の名前
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Quit => "quit",
+            Self::FocusNext => "focus_next",
+            Self::FocusPrevious => "focus_previous",
+            Self::CycleAlgorithm => "cycle_algorithm",
+            Self::CycleCase => "cycle_case",
+            Self::FocusSearch => "focus_search",
+            Self::FocusPaths => "focus_paths",
+            Self::Confirm => "confirm",
+            Self::Edit => "edit",
+            Self::SelectNext => "select_next",
+            Self::SelectPrevious => "select_previous",
+            Self::SelectFirst => "select_first",
+            Self::SelectLast => "select_last",
+            Self::SelectHalfPageDown => "select_half_page_down",
+            Self::SelectHalfPageUp => "select_half_page_up",
+            Self::Expand => "expand",
+            Self::Collapse => "collapse",
+            Self::DeleteChar => "delete_char",
+            Self::CursorLeft => "cursor_left",
+            Self::CursorRight => "cursor_right",
+            Self::ScrollDown => "scroll_down",
+            Self::ScrollUp => "scroll_up",
+            Self::ScrollTop => "scroll_top",
+            Self::ScrollBottom => "scroll_bottom",
+            Self::ScrollHalfPageDown => "scroll_half_page_down",
+            Self::ScrollHalfPageUp => "scroll_half_page_up",
+            Self::IncreaseDepth => "increase_depth",
+            Self::DecreaseDepth => "decrease_depth",
+            Self::ToggleHidden => "toggle_hidden",
+            Self::ToggleWrap => "toggle_wrap",
+            Self::ShowMore => "show_more",
+            Self::Save => "save",
+            Self::Cancel => "cancel",
+            Self::NextField => "next_field",
+            Self::TogglePersist => "toggle_persist",
+            Self::Unbound => "none",
+        }
+    }
+
+    /// 操作を割り当てられる範囲かどうか
+    fn is_allowed_in(self, scope: KeyScope) -> bool {
+        let global = matches!(
+            self,
+            Self::Quit
+                | Self::FocusNext
+                | Self::FocusPrevious
+                | Self::CycleAlgorithm
+                | Self::CycleCase
+                | Self::FocusSearch
+                | Self::FocusPaths
+        );
+        match scope {
+            KeyScope::Global => global,
+            KeyScope::Search => {
+                global
+                    || matches!(
+                        self,
+                        Self::Confirm
+                            | Self::SelectNext
+                            | Self::SelectPrevious
+                            | Self::SelectFirst
+                            | Self::SelectLast
+                            | Self::SelectHalfPageDown
+                            | Self::SelectHalfPageUp
+                            | Self::DeleteChar
+                            | Self::CursorLeft
+                            | Self::CursorRight
+                    )
+            }
+            KeyScope::Paths => {
+                global
+                    || matches!(
+                        self,
+                        Self::Confirm
+                            | Self::Edit
+                            | Self::SelectNext
+                            | Self::SelectPrevious
+                            | Self::SelectFirst
+                            | Self::SelectLast
+                            | Self::SelectHalfPageDown
+                            | Self::SelectHalfPageUp
+                            | Self::Expand
+                            | Self::Collapse
+                    )
+            }
+            KeyScope::Preview => {
+                global
+                    || matches!(
+                        self,
+                        Self::ScrollDown
+                            | Self::ScrollUp
+                            | Self::ScrollTop
+                            | Self::ScrollBottom
+                            | Self::ScrollHalfPageDown
+                            | Self::ScrollHalfPageUp
+                            | Self::IncreaseDepth
+                            | Self::DecreaseDepth
+                            | Self::ToggleHidden
+                            | Self::ToggleWrap
+                            | Self::ShowMore
+                    )
+            }
+            KeyScope::Modal => matches!(
+                self,
+                Self::Save | Self::Cancel | Self::NextField | Self::TogglePersist
+            ),
+        }
+    }
+}
+
+/// キーの割り当ての範囲
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyScope {
+    /// 全てのパネル（モーダルを除く）
+    Global,
+    Search,
+    Paths,
+    Preview,
+    /// パスの設定モーダル
+    Modal,
+}
+
+impl KeyScope {
+    /// 設定ファイルのテーブル名
+    fn name(self) -> &'static str {
+        match self {
+            Self::Global => "global",
+            Self::Search => "search",
+            Self::Paths => "paths",
+            Self::Preview => "preview",
+            Self::Modal => "modal",
+        }
+    }
+}
+
+impl From<FocusedPanel> for KeyScope {
+    fn from(panel: FocusedPanel) -> Self {
+        match panel {
+            FocusedPanel::Search => Self::Search,
+            FocusedPanel::Paths => Self::Paths,
+            FocusedPanel::Preview => Self::Preview,
+        }
+    }
+}
+
+/// 修飾キーを含むキーの組み合わせ
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyChord {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+impl KeyChord {
+    /// キーの組み合わせを作成する
+    ///
+    /// 文字キーのShiftは文字自体に含まれるため無視し、Shift-TabはBackTabとして扱う
+    pub fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        let mut modifiers =
+            modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT | KeyModifiers::SHIFT);
+        let code = match code {
+            KeyCode::Tab if modifiers.contains(KeyModifiers::SHIFT) => KeyCode::BackTab,
+            code => code,
+        };
+        if matches!(code, KeyCode::Char(_) | KeyCode::BackTab) {
+            modifiers.remove(KeyModifiers::SHIFT);
+        }
+        Self { code, modifiers }
+    }
+
+    /// 修飾キーなしで文字を入力するキーかどうか
+    fn is_text_input(self) -> bool {
+        matches!(self.code, KeyCode::Char(_)) && self.modifiers.is_empty()
+    }
+}
+
+impl FromStr for KeyChord {
+    type Err = anyhow::Error;
+
+    /// `ctrl-c`、`shift-tab`、`enter`、`G`のような表記を解析する
+    fn from_str(s: &str) -> Result<Self> {
+        const PREFIXES: [(&str, KeyModifiers); 3] = [
+            ("ctrl-", KeyModifiers::CONTROL),
+            ("alt-", KeyModifiers::ALT),
+            ("shift-", KeyModifiers::SHIFT),
+        ];
+        let mut modifiers = KeyModifiers::NONE;
+        let mut rest = s;
+        while let Some((prefix, modifier)) = PREFIXES.iter().find(|(prefix, _)| {
+            rest.len() > prefix.len()
+                && rest.is_char_boundary(prefix.len())
+                && rest[..prefix.len()].eq_ignore_ascii_case(prefix)
+        }) {
+            modifiers |= *modifier;
+            rest = &rest[prefix.len()..];
+        }
+
+        let mut chars = rest.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match rest.to_ascii_lowercase().as_str() {
+                "enter" => KeyCode::Enter,
+                "esc" | "escape" => KeyCode::Esc,
+                "tab" => KeyCode::Tab,
+                "backtab" => KeyCode::BackTab,
+                "backspace" => KeyCode::Backspace,
+                "delete" | "del" => KeyCode::Delete,
+                "space" => KeyCode::Char(' '),
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" => KeyCode::PageUp,
+                "pagedown" => KeyCode::PageDown,
+                name => match name.strip_prefix('f').and_then(|n| n.parse().ok()) {
+                    Some(n @ 1..=12) => KeyCode::F(n),
+                    _ => bail!("unknown key `{}`", s),
+                },
+            },
+        };
+        Ok(Self::new(code, modifiers))
+    }
+}
+
+impl fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "Ctrl-")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "Alt-")?;
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            write!(f, "Shift-")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "Space"),
+            KeyCode::Char(c) => write!(f, "{}", c),
+            KeyCode::BackTab => write!(f, "Shift-Tab"),
+            KeyCode::Up => write!(f, "↑"),
+            KeyCode::Down => write!(f, "↓"),
+            KeyCode::Left => write!(f, "←"),
+            KeyCode::Right => write!(f, "→"),
+            code => write!(f, "{}", code),
+        }
+    }
+}
+
+/// 組み込みのキーの割り当て
+const DEFAULT_BINDINGS: &[(KeyScope, &str, Action)] = &[
+    (KeyScope::Global, "ctrl-c", Action::Quit),
+    (KeyScope::Global, "esc", Action::Quit),
+    (KeyScope::Global, "tab", Action::FocusNext),
+    (KeyScope::Global, "shift-tab", Action::FocusPrevious),
+    (KeyScope::Global, "ctrl-t", Action::CycleAlgorithm),
+    (KeyScope::Global, "ctrl-s", Action::CycleCase),
+    (KeyScope::Search, "enter", Action::Confirm),
+    (KeyScope::Search, "down", Action::SelectNext),
+    (KeyScope::Search, "ctrl-n", Action::SelectNext),
+    (KeyScope::Search, "up", Action::SelectPrevious),
+    (KeyScope::Search, "ctrl-p", Action::SelectPrevious),
+    (KeyScope::Search, "backspace", Action::DeleteChar),
+    (KeyScope::Search, "left", Action::CursorLeft),
+    (KeyScope::Search, "right", Action::CursorRight),
+    (KeyScope::Paths, "enter", Action::Edit),
+    (KeyScope::Paths, "down", Action::SelectNext),
+    (KeyScope::Paths, "ctrl-n", Action::SelectNext),
+    (KeyScope::Paths, "up", Action::SelectPrevious),
+    (KeyScope::Paths, "ctrl-p", Action::SelectPrevious),
+    (KeyScope::Paths, "right", Action::Expand),
+    (KeyScope::Paths, "left", Action::Collapse),
+    (KeyScope::Preview, "down", Action::ScrollDown),
+    (KeyScope::Preview, "ctrl-n", Action::ScrollDown),
+    (KeyScope::Preview, "up", Action::ScrollUp),
+    (KeyScope::Preview, "ctrl-p", Action::ScrollUp),
+    (KeyScope::Preview, "+", Action::IncreaseDepth),
+    (KeyScope::Preview, "=", Action::IncreaseDepth),
+    (KeyScope::Preview, "-", Action::DecreaseDepth),
+    (KeyScope::Preview, ".", Action::ToggleHidden),
+    (KeyScope::Preview, "w", Action::ToggleWrap),
+    (KeyScope::Preview, "m", Action::ShowMore),
+    (KeyScope::Modal, "enter", Action::Save),
+    (KeyScope::Modal, "esc", Action::Cancel),
+    (KeyScope::Modal, "tab", Action::NextField),
+    (KeyScope::Modal, "up", Action::TogglePersist),
+    (KeyScope::Modal, "down", Action::TogglePersist),
+    (KeyScope::Modal, "space", Action::TogglePersist),
+];
+
+/// vim方式で組み込みの割り当てに重ねる割り当て
+///
+/// Escは終了ではなく、Searchパネルでは挿入モードを抜けてPathsパネルに移動する
+const VIM_BINDINGS: &[(KeyScope, &str, Action)] = &[
+    (KeyScope::Global, "esc", Action::Unbound),
+    (KeyScope::Search, "esc", Action::FocusPaths),
+    (KeyScope::Paths, "esc", Action::Quit),
+    (KeyScope::Paths, "q", Action::Quit),
+    (KeyScope::Paths, "j", Action::SelectNext),
+    (KeyScope::Paths, "k", Action::SelectPrevious),
+    (KeyScope::Paths, "g g", Action::SelectFirst),
+    (KeyScope::Paths, "G", Action::SelectLast),
+    (KeyScope::Paths, "ctrl-d", Action::SelectHalfPageDown),
+    (KeyScope::Paths, "ctrl-u", Action::SelectHalfPageUp),
+    (KeyScope::Paths, "/", Action::FocusSearch),
+    (KeyScope::Paths, "i", Action::FocusSearch),
+    (KeyScope::Preview, "esc", Action::Quit),
+    (KeyScope::Preview, "q", Action::Quit),
+    (KeyScope::Preview, "j", Action::ScrollDown),
+    (KeyScope::Preview, "k", Action::ScrollUp),
+    (KeyScope::Preview, "g g", Action::ScrollTop),
+    (KeyScope::Preview, "G", Action::ScrollBottom),
+    (KeyScope::Preview, "ctrl-d", Action::ScrollHalfPageDown),
+    (KeyScope::Preview, "ctrl-u", Action::ScrollHalfPageUp),
+    (KeyScope::Preview, "/", Action::FocusSearch),
+    (KeyScope::Preview, "i", Action::FocusSearch),
+];
+
+/// キーの並びと操作の対応
+type Binding = (Vec<KeyChord>, Action);
+
+/// `g g`のように空白で区切ったキーの並びを解析する
+fn parse_sequence(key: &str) -> Result<Vec<KeyChord>> {
+    let sequence = key
+        .split_whitespace()
+        .map(str::parse)
+        .collect::<Result<Vec<KeyChord>>>()?;
+    if sequence.is_empty() {
+        bail!("empty key `{}`", key);
+    }
+    Ok(sequence)
+}
+
+/// キーの並びをヘルプ用に表示する（修飾キーのない文字だけの場合は`gg`のように続けて表示する）
+fn format_sequence(sequence: &[KeyChord]) -> String {
+    let separator = if sequence.iter().all(|chord| chord.is_text_input()) {
+        ""
+    } else {
+        " "
+    };
+    sequence
+        .iter()
+        .map(|chord| chord.to_string())
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+/// キーの並びに対応する操作の検索結果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyLookup {
+    /// 操作が割り当てられている
+    Action(Action),
+    /// 続きのキーを入力すると操作が決まる
+    Pending,
+    /// 割り当てがない
+    Unbound,
+}
+
+/// 範囲ごとのキーと操作の対応
+#[derive(Debug, Clone, PartialEq)]
+pub struct Keymap {
+    global: Vec<Binding>,
+    search: Vec<Binding>,
+    paths: Vec<Binding>,
+    preview: Vec<Binding>,
+    modal: Vec<Binding>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self::new(&KeysConfig::default()).expect("default key bindings are valid")
+    }
+}
+
+impl Keymap {
+    /// 組み込みの割り当てに設定ファイルの割り当てを重ねたキーマップを作成する
+    ///
+    /// 不明なキーや範囲外の操作、範囲の間で衝突するキーがある場合はエラーを返す
+    pub fn new(config: &KeysConfig) -> Result<Self> {
+        let mut keymap = Self {
+            global: Vec::new(),
+            search: Vec::new(),
+            paths: Vec::new(),
+            preview: Vec::new(),
+            modal: Vec::new(),
+        };
+        let builtin = match config.mode {
+            KeyMode::Default => &[][..],
+            KeyMode::Vim => VIM_BINDINGS,
+        };
+        for &(scope, key, action) in DEFAULT_BINDINGS.iter().chain(builtin) {
+            keymap.bind(scope, parse_sequence(key)?, action);
+        }
+
+        for (scope, bindings) in [
+            (KeyScope::Global, &config.global),
+            (KeyScope::Search, &config.search),
+            (KeyScope::Paths, &config.paths),
+            (KeyScope::Preview, &config.preview),
+            (KeyScope::Modal, &config.modal),
+        ] {
+            let mut seen: Vec<Vec<KeyChord>> = Vec::new();
+            for (key, action) in bindings {
+                let action: Action = action.parse()?;
+                let sequence = parse_sequence(key)?;
+                if seen.contains(&sequence) {
+                    bail!(
+                        "`{}` is bound more than once in [keys.{}]",
+                        key,
+                        scope.name()
+                    );
+                }
+                seen.push(sequence.clone());
+                if action != Action::Unbound && !action.is_allowed_in(scope) {
+                    bail!(
+                        "`{}` cannot be bound in [keys.{}]",
+                        action.name(),
+                        scope.name()
+                    );
+                }
+                keymap.bind(scope, sequence, action);
+            }
+        }
+
+        keymap.validate()?;
+        Ok(keymap)
+    }
+
+    /// 範囲の間で衝突するキーがないか確認する
+    fn validate(&self) -> Result<()> {
+        // 修飾キーなしの文字で始まる並びは、Searchパネルでその文字を入力できなくする
+        for (scope, bindings) in [
+            (KeyScope::Global, &self.global),
+            (KeyScope::Search, &self.search),
+        ] {
+            if let Some((sequence, action)) = bindings
+                .iter()
+                .find(|(sequence, _)| sequence[0].is_text_input())
+            {
+                bail!(
+                    "`{}` is bound to `{}` in [keys.{}] and would prevent typing in the Search panel",
+                    format_sequence(sequence),
+                    action.name(),
+                    scope.name()
+                );
+            }
+        }
+
+        // モーダルのTags入力も同様（toggle_persistはPersistにフォーカスがある時だけ使われる）
+        if let Some((sequence, action)) = self.modal.iter().find(|(sequence, action)| {
+            sequence[0].is_text_input() && *action != Action::TogglePersist
+        }) {
+            bail!(
+                "`{}` is bound to `{}` in [keys.modal] and would prevent typing in the Tags field",
+                format_sequence(sequence),
+                action.name()
+            );
+        }
+
+        for (sequence, action) in &self.global {
+            for scope in [KeyScope::Search, KeyScope::Paths, KeyScope::Preview] {
+                if let Some(other) = self.lookup(scope, sequence).filter(|other| other != action) {
+                    bail!(
+                        "`{}` is bound to both `{}` in [keys.global] and `{}` in [keys.{}]",
+                        format_sequence(sequence),
+                        action.name(),
+                        other.name(),
+                        scope.name()
+                    );
+                }
+            }
+        }
+
+        // 他のキーの並びの先頭と一致するキーは、続きのキーを待つため使えない
+        for scope in [
+            KeyScope::Search,
+            KeyScope::Paths,
+            KeyScope::Preview,
+            KeyScope::Modal,
+        ] {
+            let bindings: Vec<&Binding> = self.visible_bindings(scope).collect();
+            for (prefix, _) in &bindings {
+                if let Some((sequence, _)) = bindings.iter().find(|(sequence, _)| {
+                    sequence.len() > prefix.len() && sequence.starts_with(prefix)
+                }) {
+                    bail!(
+                        "`{}` cannot be bound because `{}` starts with it in [keys.{}]",
+                        format_sequence(prefix),
+                        format_sequence(sequence),
+                        scope.name()
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn bindings(&self, scope: KeyScope) -> &Vec<Binding> {
+        match scope {
+            KeyScope::Global => &self.global,
+            KeyScope::Search => &self.search,
+            KeyScope::Paths => &self.paths,
+            KeyScope::Preview => &self.preview,
+            KeyScope::Modal => &self.modal,
+        }
+    }
+
+    /// 範囲で使われる割り当て（パネルの割り当て、全てのパネルの割り当ての順）
+    fn visible_bindings(&self, scope: KeyScope) -> impl Iterator<Item = &Binding> {
+        let global = match scope {
+            KeyScope::Modal => &[][..],
+            _ => &self.global[..],
+        };
+        self.bindings(scope).iter().chain(global)
+    }
+
+    /// キーを割り当てる（既に割り当てられている場合は置き換え、`Unbound`の場合は解除する）
+    fn bind(&mut self, scope: KeyScope, sequence: Vec<KeyChord>, action: Action) {
+        let bindings = match scope {
+            KeyScope::Global => &mut self.global,
+            KeyScope::Search => &mut self.search,
+            KeyScope::Paths => &mut self.paths,
+            KeyScope::Preview => &mut self.preview,
+            KeyScope::Modal => &mut self.modal,
+        };
+        match bindings.iter().position(|(bound, _)| *bound == sequence) {
+            Some(position) if action == Action::Unbound => {
+                bindings.remove(position);
+            }
+            Some(position) => bindings[position].1 = action,
+            None if action == Action::Unbound => {}
+            None => bindings.push((sequence, action)),
+        }
+    }
+
+    /// 範囲だけに割り当てられた操作を探す
+    fn lookup(&self, scope: KeyScope, sequence: &[KeyChord]) -> Option<Action> {
+        self.bindings(scope)
+            .iter()
+            .find(|(bound, _)| bound == sequence)
+            .map(|(_, action)| *action)
+    }
+
+    /// 入力したキーの並びに割り当てられた操作を取得する
+    ///
+    /// パネルの割り当てがない場合は全てのパネルの割り当てを使う（モーダルでは使わない）
+    pub fn action(&self, scope: KeyScope, sequence: &[KeyChord]) -> KeyLookup {
+        let found = self.lookup(scope, sequence).or_else(|| match scope {
+            KeyScope::Modal => None,
+            _ => self.lookup(KeyScope::Global, sequence),
+        });
+        match found {
+            Some(action) => KeyLookup::Action(action),
+            None if self
+                .visible_bindings(scope)
+                .any(|(bound, _)| bound.starts_with(sequence)) =>
+            {
+                KeyLookup::Pending
+            }
+            None => KeyLookup::Unbound,
+        }
+    }
+
+    /// 操作に割り当てられたキーをヘルプ用に`/`で区切って取得する（割り当てがない場合は空文字列）
+    pub fn keys(&self, scope: KeyScope, action: Action) -> String {
+        self.visible_bindings(scope)
+            .filter(|(_, bound)| *bound == action)
+            .map(|(sequence, _)| format_sequence(sequence))
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+
+    /// 操作に割り当てられた最初のキーをヘルプ用に取得する（割り当てがない場合は`None`）
+    pub fn key(&self, scope: KeyScope, action: Action) -> Option<String> {
+        self.visible_bindings(scope)
+            .find(|(_, bound)| *bound == action)
+            .map(|(sequence, _)| format_sequence(sequence))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use std::collections::BTreeMap;
+
+    fn chord(code: KeyCode, modifiers: KeyModifiers) -> KeyChord {
+        KeyChord::new(code, modifiers)
+    }
+
+    #[test]
+    fn test_key_chord_表記を解析できる() {
+        // Act & Assert
+        assert_eq!(
+            "Ctrl-C".parse::<KeyChord>().unwrap(),
+            chord(KeyCode::Char('C'), KeyModifiers::CONTROL)
+        );
+        assert_eq!(
+            "shift-tab".parse::<KeyChord>().unwrap(),
+            chord(KeyCode::BackTab, KeyModifiers::SHIFT)
+        );
+        assert_eq!(
+            "ctrl--".parse::<KeyChord>().unwrap(),
+            chord(KeyCode::Char('-'), KeyModifiers::CONTROL)
+        );
+        assert_eq!(
+            "G".parse::<KeyChord>().unwrap(),
+            chord(KeyCode::Char('G'), KeyModifiers::SHIFT)
+        );
+        assert_eq!(
+            "f5".parse::<KeyChord>().unwrap(),
+            chord(KeyCode::F(5), KeyModifiers::NONE)
+        );
+        assert!("ctrl-foo".parse::<KeyChord>().is_err());
+    }
+
+    #[test]
+    fn test_action_パネルの割り当てを優先し全体の割り当てにフォールバックする() {
+        // Arrange
+        let keymap = Keymap::default();
+        let down = chord(KeyCode::Down, KeyModifiers::NONE);
+        let esc = chord(KeyCode::Esc, KeyModifiers::NONE);
+
+        // Act & Assert
+        assert_eq!(
+            keymap.action(KeyScope::Paths, &[down]),
+            KeyLookup::Action(Action::SelectNext)
+        );
+        assert_eq!(
+            keymap.action(KeyScope::Preview, &[down]),
+            KeyLookup::Action(Action::ScrollDown)
+        );
+        assert_eq!(
+            keymap.action(KeyScope::Preview, &[esc]),
+            KeyLookup::Action(Action::Quit)
+        );
+        assert_eq!(
+            keymap.action(KeyScope::Modal, &[esc]),
+            KeyLookup::Action(Action::Cancel)
+        );
+    }
+
+    #[test]
+    fn test_new_設定の割り当てで上書きと解除ができる() {
+        // Arrange
+        let config = KeysConfig {
+            global: BTreeMap::from([("ctrl-q".to_string(), "quit".to_string())]),
+            preview: BTreeMap::from([
+                ("j".to_string(), "scroll_down".to_string()),
+                ("w".to_string(), "none".to_string()),
+            ]),
+            ..KeysConfig::default()
+        };
+
+        // Act
+        let keymap = Keymap::new(&config).unwrap();
+
+        // Assert
+        assert_eq!(
+            keymap.action(
+                KeyScope::Search,
+                &[chord(KeyCode::Char('q'), KeyModifiers::CONTROL)]
+            ),
+            KeyLookup::Action(Action::Quit)
+        );
+        assert_eq!(
+            keymap.action(
+                KeyScope::Preview,
+                &[chord(KeyCode::Char('j'), KeyModifiers::NONE)]
+            ),
+            KeyLookup::Action(Action::ScrollDown)
+        );
+        assert_eq!(
+            keymap.action(
+                KeyScope::Preview,
+                &[chord(KeyCode::Char('w'), KeyModifiers::NONE)]
+            ),
+            KeyLookup::Unbound
+        );
+        assert_eq!(
+            keymap.keys(KeyScope::Search, Action::Quit),
+            "Ctrl-c/Esc/Ctrl-q"
+        );
+    }
+
+    #[test]
+    fn test_new_衝突する割り当てはエラーになる() {
+        // Arrange
+        let conflicts = [
+            KeysConfig {
+                paths: BTreeMap::from([("tab".to_string(), "expand".to_string())]),
+                ..KeysConfig::default()
+            },
+            KeysConfig {
+                global: BTreeMap::from([("q".to_string(), "quit".to_string())]),
+                ..KeysConfig::default()
+            },
+            KeysConfig {
+                search: BTreeMap::from([("ctrl-j".to_string(), "scroll_down".to_string())]),
+                ..KeysConfig::default()
+            },
+            KeysConfig {
+                search: BTreeMap::from([("x".to_string(), "select_next".to_string())]),
+                ..KeysConfig::default()
+            },
+            KeysConfig {
+                search: BTreeMap::from([("g g".to_string(), "select_next".to_string())]),
+                ..KeysConfig::default()
+            },
+            KeysConfig {
+                preview: BTreeMap::from([
+                    ("Ctrl-j".to_string(), "scroll_down".to_string()),
+                    ("ctrl-j".to_string(), "scroll_up".to_string()),
+                ]),
+                ..KeysConfig::default()
+            },
+        ];
+
+        // Act & Assert
+        for config in &conflicts {
+            assert!(Keymap::new(config).is_err(), "{:?}", config);
+        }
+    }
+
+    #[test]
+    fn test_keys_ヘルプ用にキーを表示する() {
+        // Arrange
+        let keymap = Keymap::default();
+
+        // Act & Assert
+        assert_eq!(
+            keymap.keys(KeyScope::Modal, Action::TogglePersist),
+            "↑/↓/Space"
+        );
+        assert_eq!(
+            keymap.key(KeyScope::Paths, Action::FocusNext).as_deref(),
+            Some("Tab")
+        );
+        assert_eq!(keymap.key(KeyScope::Modal, Action::Quit), None);
+    }
+
+    #[test]
+    fn test_action_vim方式ではキーの並びの続きを待つ() {
+        // Arrange
+        let config = KeysConfig {
+            mode: KeyMode::Vim,
+            ..KeysConfig::default()
+        };
+        let keymap = Keymap::new(&config).unwrap();
+        let g = chord(KeyCode::Char('g'), KeyModifiers::NONE);
+        let esc = chord(KeyCode::Esc, KeyModifiers::NONE);
+
+        // Act & Assert
+        assert_eq!(keymap.action(KeyScope::Paths, &[g]), KeyLookup::Pending);
+        assert_eq!(
+            keymap.action(KeyScope::Paths, &[g, g]),
+            KeyLookup::Action(Action::SelectFirst)
+        );
+        assert_eq!(
+            keymap.action(KeyScope::Search, &[esc]),
+            KeyLookup::Action(Action::FocusPaths)
+        );
+        assert_eq!(keymap.action(KeyScope::Search, &[g]), KeyLookup::Unbound);
+        assert_eq!(keymap.keys(KeyScope::Preview, Action::ScrollTop), "gg");
+    }
+
+    #[test]
+    fn test_new_モーダルの文字キーはtoggle_persistにだけ割り当てられる() {
+        // Arrange
+        let toggle = KeysConfig {
+            modal: BTreeMap::from([("t".to_string(), "toggle_persist".to_string())]),
+            ..KeysConfig::default()
+        };
+        let save = KeysConfig {
+            modal: BTreeMap::from([("x".to_string(), "save".to_string())]),
+            ..KeysConfig::default()
+        };
+
+        // Act
+        let toggle_result = Keymap::new(&toggle);
+        let save_result = Keymap::new(&save);
+
+        // Assert
+        assert!(toggle_result.is_ok());
+        let err = save_result.unwrap_err().to_string();
+        assert!(
+            err.contains("would prevent typing in the Tags field"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_new_キーの並びの先頭と一致するキーはエラーになる() {
+        // Arrange
+        let config = KeysConfig {
+            mode: KeyMode::Vim,
+            paths: BTreeMap::from([("g".to_string(), "expand".to_string())]),
+            ..KeysConfig::default()
+        };
+
+        // Act
+        let result = Keymap::new(&config);
+
+        // Assert
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_new_設定ファイルの割り当てが衝突する場合はエラーになる() {
+        // Arrange
+        let content = r#"
+            [keys.global]
+            q = "quit"
+
+            [keys.preview]
+            j = "scroll_down"
+        "#;
+        let config: Config = toml::from_str(content).unwrap();
+
+        // Act
+        let result = Keymap::new(&config.keys);
+
+        // Assert
+        let error = format!("{:#}", result.unwrap_err());
+        assert!(error.contains("prevent typing"), "{}", error);
+    }
+
+    #[test]
+    fn test_new_不明な操作の名前はエラーになる() {
+        // Arrange
+        let config = KeysConfig {
+            paths: BTreeMap::from([("j".to_string(), "jump".to_string())]),
+            ..KeysConfig::default()
+        };
+
+        // Act
+        let result = Keymap::new(&config);
+
+        // Assert
+        let error = format!("{:#}", result.unwrap_err());
+        assert!(error.contains("Unknown action `jump`"), "{}", error);
+    }
+}
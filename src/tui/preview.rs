@@ -0,0 +1,500 @@
+use lru::LruCache;
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::Line,
+};
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::thread;
+use std::time::SystemTime;
+
+use crate::config::{ConfigPath, PreviewConfig};
+use crate::entry::{error_line, metadata};
+use crate::Pavo;
+
+/// キャッシュするプレビューの最大数
+const CACHE_CAPACITY: usize = 64;
+
+/// ワーカースレッドへのプレビュー生成リクエスト
+struct PreviewRequest {
+    id: u64,
+    path: PathBuf,
+    config: PreviewConfig,
+    /// ヘッダーに統計を表示するブックマーク
+    bookmark: Option<ConfigPath>,
+    /// キャッシュ済みのプレビューを生成した時点の更新日時（再生成が必要な場合は`None`）
+    cached_mtime: Option<SystemTime>,
+}
+
+/// ワーカースレッドからの生成結果
+struct PreviewResponse {
+    id: u64,
+    path: PathBuf,
+    config: PreviewConfig,
+    bookmark: Option<ConfigPath>,
+    mtime: Option<SystemTime>,
+    /// ディレクトリのサイズの計算中に生成したかどうか（計算中または前回のサイズを表示した）
+    size_pending: bool,
+    /// 新しく生成したプレビュー（キャッシュが最新の場合は`None`）
+    lines: Option<Vec<Line<'static>>>,
+}
+
+/// キャッシュ済みのプレビュー
+struct CachedPreview {
+    mtime: Option<SystemTime>,
+    /// ディレクトリのサイズの計算中に生成したかどうか（計算中または前回のサイズを表示した）
+    size_pending: bool,
+    bookmark: Option<ConfigPath>,
+    lines: Vec<Line<'static>>,
+}
+
+impl CachedPreview {
+    /// 更新日時以外の理由で再生成が必要かどうか
+    ///
+    /// ブックマークの統計が変わった場合や、ディレクトリのサイズの計算が終わった場合は再生成する
+    fn is_outdated(&self, path: &Path, bookmark: Option<&ConfigPath>) -> bool {
+        self.bookmark.as_ref() != bookmark
+            || (self.size_pending && metadata::is_dir_size_ready(path))
+    }
+}
+
+/// プレビューをバックグラウンドで生成し、パスとプレビューの設定をキーにキャッシュする
+///
+/// キャッシュは更新日時で検証し、変更されていれば再生成する。
+/// ブックマークの統計が変わった場合や、ディレクトリのサイズの計算が終わった場合も再生成する。
+/// リポジトリ内のディレクトリのプレビューは作業ツリーやrefsの状態にも依存するため、
+/// キャッシュはすぐに表示するためだけに使い、毎回再生成する。
+///
+/// ワーカースレッドはキューに溜まったリクエストのうち最新のものだけを処理し、
+/// 古いリクエストの結果は画面に反映しない（キャッシュには格納する）。
+/// ワーカースレッドが異常終了した場合は、エラーを表示して起動し直す。
+pub struct PreviewLoader {
+    request_tx: Sender<PreviewRequest>,
+    response_rx: Receiver<PreviewResponse>,
+    /// ワーカースレッドでプレビューを生成する関数
+    generator: fn(PreviewRequest) -> PreviewResponse,
+    cache: LruCache<(PathBuf, PreviewConfig), CachedPreview>,
+    /// 最後に発行したリクエストのID
+    latest_id: u64,
+    /// 結果待ちのリクエストのID
+    pending_id: Option<u64>,
+    /// 表示中のプレビューの生成時にサイズを計算中だったディレクトリ
+    size_pending_path: Option<PathBuf>,
+}
+
+impl Default for PreviewLoader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PreviewLoader {
+    /// ワーカースレッドを起動してPreviewLoaderを作成する
+    ///
+    /// ワーカースレッドはPreviewLoaderが破棄されると終了する
+    pub fn new() -> Self {
+        Self::with_generator(generate)
+    }
+
+    fn with_generator(generator: fn(PreviewRequest) -> PreviewResponse) -> Self {
+        let (request_tx, response_rx) = spawn_worker(generator);
+        Self {
+            request_tx,
+            response_rx,
+            generator,
+            cache: LruCache::new(NonZeroUsize::new(CACHE_CAPACITY).unwrap()),
+            latest_id: 0,
+            pending_id: None,
+            size_pending_path: None,
+        }
+    }
+
+    /// プレビューの生成をリクエストする
+    ///
+    /// # Returns
+    /// キャッシュ済みのプレビューがあればそれを返す。
+    /// 更新日時が変わっていた場合は、生成後に`poll`から新しいプレビューが返る。
+    pub fn request(
+        &mut self,
+        path: &Path,
+        config: &PreviewConfig,
+        bookmark: Option<&ConfigPath>,
+    ) -> Option<Vec<Line<'static>>> {
+        self.latest_id += 1;
+        let cached = self.cache.get(&(path.to_path_buf(), config.clone()));
+        let request = PreviewRequest {
+            id: self.latest_id,
+            path: path.to_path_buf(),
+            config: config.clone(),
+            bookmark: bookmark.cloned(),
+            cached_mtime: cached
+                .filter(|c| !c.is_outdated(path, bookmark))
+                .and_then(|c| c.mtime),
+        };
+        self.size_pending_path = cached
+            .is_some_and(|c| c.size_pending)
+            .then(|| path.to_path_buf());
+        let lines = cached.map(|c| c.lines.clone());
+        // ワーカースレッドが終了している場合は起動し直して送り直す
+        let sent = match self.request_tx.send(request) {
+            Ok(()) => true,
+            Err(mpsc::SendError(request)) => {
+                self.restart_worker();
+                self.request_tx.send(request).is_ok()
+            }
+        };
+        self.pending_id = sent.then_some(self.latest_id);
+        lines
+    }
+
+    fn restart_worker(&mut self) {
+        (self.request_tx, self.response_rx) = spawn_worker(self.generator);
+    }
+
+    /// 結果待ちのリクエストを取り消す
+    pub fn cancel(&mut self) {
+        self.pending_id = None;
+        self.size_pending_path = None;
+    }
+
+    /// 表示中のプレビューの生成時に計算中だったディレクトリのサイズの計算が終わったかどうか
+    pub fn is_size_ready(&self) -> bool {
+        self.size_pending_path
+            .as_deref()
+            .is_some_and(metadata::is_dir_size_ready)
+    }
+
+    /// 結果待ちのリクエストがあるかどうか
+    pub fn is_pending(&self) -> bool {
+        self.pending_id.is_some()
+    }
+
+    /// ワーカースレッドの結果を受け取る
+    ///
+    /// # Returns
+    /// 最新のリクエストに対して新しいプレビューが生成された場合はそれを返す。
+    /// 生成中にワーカースレッドが異常終了した場合はエラーを返す
+    pub fn poll(&mut self) -> Option<Vec<Line<'static>>> {
+        let mut latest = None;
+        loop {
+            let response = match self.response_rx.try_recv() {
+                Ok(response) => response,
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    if self.pending_id.take().is_some() {
+                        latest = Some(vec![error_line(
+                            "Preview generation stopped unexpectedly".to_string(),
+                        )]);
+                    }
+                    self.restart_worker();
+                    break;
+                }
+            };
+            let is_latest = self.pending_id == Some(response.id);
+            if is_latest {
+                self.pending_id = None;
+            }
+            if let Some(lines) = response.lines {
+                if is_latest {
+                    latest = Some(lines.clone());
+                    self.size_pending_path = response.size_pending.then(|| response.path.clone());
+                }
+                self.cache.put(
+                    (response.path, response.config),
+                    CachedPreview {
+                        mtime: response.mtime,
+                        size_pending: response.size_pending,
+                        bookmark: response.bookmark,
+                        lines,
+                    },
+                );
+            }
+        }
+        latest
+    }
+}
+
+/// プレビューを生成するワーカースレッドを起動する
+fn spawn_worker(
+    generator: fn(PreviewRequest) -> PreviewResponse,
+) -> (Sender<PreviewRequest>, Receiver<PreviewResponse>) {
+    let (request_tx, request_rx) = mpsc::channel::<PreviewRequest>();
+    let (response_tx, response_rx) = mpsc::channel();
+
+    thread::spawn(move || {
+        while let Ok(mut request) = request_rx.recv() {
+            // 溜まっている古いリクエストは破棄して最新のものだけを処理する
+            while let Ok(newer) = request_rx.try_recv() {
+                request = newer;
+            }
+            let response = generator(request);
+            if response_tx.send(response).is_err() {
+                break;
+            }
+        }
+    });
+
+    (request_tx, response_rx)
+}
+
+/// プレビューの生成中に表示するプレースホルダー
+pub fn loading_placeholder() -> Vec<Line<'static>> {
+    vec![Line::styled(
+        "Loading preview...",
+        Style::default()
+            .fg(Color::DarkGray)
+            .add_modifier(Modifier::ITALIC),
+    )]
+}
+
+/// ワーカースレッドでプレビューを生成する
+fn generate(request: PreviewRequest) -> PreviewResponse {
+    // 生成前に確認し、生成中に計算が終わった場合も後で再生成されるようにする
+    let size_pending = request.path.is_dir() && !metadata::is_dir_size_ready(&request.path);
+    let mtime = std::fs::metadata(&request.path)
+        .and_then(|metadata| metadata.modified())
+        .ok();
+    let is_fresh =
+        mtime.is_some() && mtime == request.cached_mtime && !depends_on_repository(&request.path);
+    let lines = if is_fresh {
+        None
+    } else {
+        Some(
+            Pavo::get_entry_preview(&request.path, &request.config, request.bookmark.as_ref())
+                .unwrap_or_default(),
+        )
+    };
+    PreviewResponse {
+        id: request.id,
+        path: request.path,
+        config: request.config,
+        bookmark: request.bookmark,
+        mtime,
+        size_pending,
+        lines,
+    }
+}
+
+/// プレビューがリポジトリの状態（作業ツリーの変更やrefs）に依存するかどうか
+///
+/// リポジトリ内のディレクトリはブランチや変更の状況を表示するため、ディレクトリ自体の更新日時では検証できない
+fn depends_on_repository(path: &Path) -> bool {
+    path.is_dir() && Pavo::find_repo_root(path).is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helper::{lines_to_string, setup_test_repo};
+    use std::fs;
+    use std::time::{Duration, Instant};
+    use tempfile::tempdir;
+
+    /// 最新のリクエストの結果が届くまで待つ
+    fn wait_for_preview(loader: &mut PreviewLoader) -> Option<Vec<Line<'static>>> {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while Instant::now() < deadline {
+            if let Some(lines) = loader.poll() {
+                return Some(lines);
+            }
+            if !loader.is_pending() {
+                return None;
+            }
+            thread::sleep(Duration::from_millis(5));
+        }
+        None
+    }
+
+    #[test]
+    fn test_request_バックグラウンドでプレビューが生成される() {
+        // Arrange
+        let temp_dir = tempdir().unwrap();
+        let file = temp_dir.path().join("file.txt");
+        fs::write(&file, "background content").unwrap();
+        let mut loader = PreviewLoader::new();
+
+        // Act
+        let cached = loader.request(&file, &PreviewConfig::default(), None);
+        let preview = wait_for_preview(&mut loader);
+
+        // Assert
+        assert!(cached.is_none());
+        assert!(lines_to_string(&preview.unwrap()).contains("background content"));
+        assert!(!loader.is_pending());
+    }
+
+    #[test]
+    fn test_request_キャッシュ済みのプレビューを即座に返す() {
+        // Arrange
+        let temp_dir = tempdir().unwrap();
+        let file = temp_dir.path().join("file.txt");
+        fs::write(&file, "cached content").unwrap();
+        let mut loader = PreviewLoader::new();
+        loader.request(&file, &PreviewConfig::default(), None);
+        wait_for_preview(&mut loader).unwrap();
+
+        // Act
+        let cached = loader.request(&file, &PreviewConfig::default(), None);
+        let refreshed = wait_for_preview(&mut loader);
+
+        // Assert
+        assert!(lines_to_string(&cached.unwrap()).contains("cached content"));
+        assert!(refreshed.is_none()); // 更新日時が同じなので再生成されない
+    }
+
+    #[test]
+    fn test_request_更新日時が変わると再生成される() {
+        // Arrange
+        let temp_dir = tempdir().unwrap();
+        let file = temp_dir.path().join("file.txt");
+        fs::write(&file, "old content").unwrap();
+        let mut loader = PreviewLoader::new();
+        loader.request(&file, &PreviewConfig::default(), None);
+        wait_for_preview(&mut loader).unwrap();
+
+        // Act
+        fs::write(&file, "new content").unwrap();
+        fs::File::options()
+            .write(true)
+            .open(&file)
+            .unwrap()
+            .set_modified(SystemTime::now() + Duration::from_secs(60))
+            .unwrap();
+        let cached = loader.request(&file, &PreviewConfig::default(), None);
+        let refreshed = wait_for_preview(&mut loader);
+
+        // Assert
+        assert!(lines_to_string(&cached.unwrap()).contains("old content"));
+        assert!(lines_to_string(&refreshed.unwrap()).contains("new content"));
+    }
+
+    #[test]
+    fn test_request_ブックマークの統計が変わると再生成される() {
+        // Arrange
+        let temp_dir = tempdir().unwrap();
+        let file = temp_dir.path().join("file.txt");
+        fs::write(&file, "content").unwrap();
+        let mut bookmark = ConfigPath {
+            path: file.clone(),
+            last_selected: chrono::Utc::now(),
+            persist: false,
+            tags: vec!["old".to_string()],
+            access_count: 0,
+        };
+        let mut loader = PreviewLoader::new();
+        loader.request(&file, &PreviewConfig::default(), Some(&bookmark));
+        wait_for_preview(&mut loader).unwrap();
+
+        // Act
+        bookmark.tags = vec!["new".to_string()];
+        let cached = loader.request(&file, &PreviewConfig::default(), Some(&bookmark));
+        let refreshed = wait_for_preview(&mut loader);
+
+        // Assert
+        assert!(lines_to_string(&cached.unwrap()).contains("Tags: old"));
+        assert!(lines_to_string(&refreshed.unwrap()).contains("Tags: new"));
+    }
+
+    #[test]
+    fn test_poll_古いリクエストの結果は返さない() {
+        // Arrange
+        let temp_dir = tempdir().unwrap();
+        let first = temp_dir.path().join("first.txt");
+        let second = temp_dir.path().join("second.txt");
+        fs::write(&first, "first content").unwrap();
+        fs::write(&second, "second content").unwrap();
+        let mut loader = PreviewLoader::new();
+
+        // Act
+        loader.request(&first, &PreviewConfig::default(), None);
+        loader.request(&second, &PreviewConfig::default(), None);
+        let preview = wait_for_preview(&mut loader);
+
+        // Assert
+        assert!(lines_to_string(&preview.unwrap()).contains("second content"));
+    }
+
+    #[test]
+    fn test_cancel_結果待ちが解除される() {
+        // Arrange
+        let temp_dir = tempdir().unwrap();
+        let mut loader = PreviewLoader::new();
+        loader.request(temp_dir.path(), &PreviewConfig::default(), None);
+
+        // Act
+        loader.cancel();
+
+        // Assert
+        assert!(!loader.is_pending());
+    }
+
+    #[test]
+    fn test_request_設定が変わると別のプレビューとして生成される() {
+        // Arrange
+        let temp_dir = tempdir().unwrap();
+        fs::write(temp_dir.path().join(".hidden"), "").unwrap();
+        let mut loader = PreviewLoader::new();
+        loader.request(temp_dir.path(), &PreviewConfig::default(), None);
+        wait_for_preview(&mut loader).unwrap();
+        let config = PreviewConfig {
+            show_hidden: true,
+            ..PreviewConfig::default()
+        };
+
+        // Act
+        let cached = loader.request(temp_dir.path(), &config, None);
+        let preview = wait_for_preview(&mut loader);
+
+        // Assert
+        assert!(cached.is_none());
+        assert!(lines_to_string(&preview.unwrap()).contains(".hidden"));
+    }
+
+    #[test]
+    fn test_request_リポジトリは更新日時が同じでも再生成される() {
+        // Arrange
+        let temp_dir = tempdir().unwrap();
+        setup_test_repo(&temp_dir);
+        let mut loader = PreviewLoader::new();
+        loader.request(temp_dir.path(), &PreviewConfig::default(), None);
+        let before = wait_for_preview(&mut loader).unwrap();
+
+        // Act
+        fs::write(temp_dir.path().join("test.txt"), "modified").unwrap();
+        let cached = loader.request(temp_dir.path(), &PreviewConfig::default(), None);
+        let refreshed = wait_for_preview(&mut loader);
+
+        // Assert
+        assert_eq!(cached, Some(before));
+        assert!(lines_to_string(&refreshed.unwrap()).contains("1 modified"));
+    }
+
+    #[test]
+    fn test_poll_ワーカースレッドが異常終了した場合はエラーを表示して起動し直す() {
+        // Arrange
+        let temp_dir = tempdir().unwrap();
+        let file = temp_dir.path().join("file.txt");
+        fs::write(&file, "content").unwrap();
+        let mut loader = PreviewLoader::with_generator(|request| {
+            if request.path.ends_with("panic.txt") {
+                panic!("preview generation failed");
+            }
+            generate(request)
+        });
+
+        // Act
+        loader.request(
+            &temp_dir.path().join("panic.txt"),
+            &PreviewConfig::default(),
+            None,
+        );
+        let failed = wait_for_preview(&mut loader);
+        loader.request(&file, &PreviewConfig::default(), None);
+        let recovered = wait_for_preview(&mut loader);
+
+        // Assert
+        assert!(lines_to_string(&failed.unwrap()).contains("stopped unexpectedly"));
+        assert!(lines_to_string(&recovered.unwrap()).contains("content"));
+    }
+}
@@ -8,7 +8,10 @@ use std::path::PathBuf;
 
 use super::app::App;
 use super::event::handle_event;
-use super::ui::ui;
+use super::keymap::Keymap;
+use super::theme::{self, Theme};
+use super::ui::{page_height, ui};
+use crate::entry::state::{PathState, UnavailablePathError};
 use crate::Pavo;
 
 /// TUIを実行する
@@ -17,6 +20,10 @@ use crate::Pavo;
 /// * `pavo` - Pavoインスタンス
 /// * `tag_filter` - タグフィルター
 pub fn run_tui(pavo: &mut Pavo, tag_filter: Option<&str>) -> Result<()> {
+    // 端末を切り替える前にキーの割り当てを確認する
+    let keymap = Keymap::new(&pavo.get_config().keys)
+        .with_context(|| format!("Invalid [keys] in {}", pavo.get_config_file().display()))?;
+
     // ターミナルのセットアップ
     enable_raw_mode().context("Failed to enable raw mode")?;
     let mut tty = std::fs::OpenOptions::new()
@@ -46,11 +53,20 @@ pub fn run_tui(pavo: &mut Pavo, tag_filter: Option<&str>) -> Result<()> {
         .iter()
         .map(|config_path| config_path.path.clone())
         .collect();
-    let mut app = App::new(paths, tag_filter.map(|s| s.to_string()));
+    let mut app = App::new(paths, tag_filter.map(|s| s.to_string()))
+        .with_config_paths(&config_paths)
+        .with_search_config(&pavo.get_config().search)
+        .with_list_config(&pavo.get_config().list)
+        .with_theme(Theme::new(&pavo.get_config().theme, theme::no_color()))
+        .with_keymap(keymap)
+        .with_preview_config(&pavo.get_config().preview_config());
 
     loop {
-        terminal.draw(|f| ui(f, &app, pavo))?;
+        let frame = terminal.draw(|f| ui(f, &app, pavo))?;
+        app.set_page_height(page_height(frame.area));
         handle_event(&mut app, pavo)?;
+        app.poll_attributes();
+        app.poll_preview();
 
         if app.should_quit() {
             break;
@@ -64,6 +80,15 @@ pub fn run_tui(pavo: &mut Pavo, tag_filter: Option<&str>) -> Result<()> {
 
     // 選択されたパスを処理
     if let Some(path) = app.selected_path() {
+        // 開けないパスはシェルに渡さず、状態を伝えるエラーにする
+        let state = PathState::detect(path);
+        if !state.is_available() {
+            return Err(UnavailablePathError {
+                path: path.clone(),
+                state,
+            }
+            .into());
+        }
         pavo.update_last_selected(path)?;
         println!("{}", path.display());
     }
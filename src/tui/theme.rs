@@ -0,0 +1,302 @@
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::Line;
+
+use crate::config::{ThemeConfig, ThemePreset};
+
+/// TUIの描画に使う配色
+#[derive(Debug, Clone, PartialEq)]
+pub struct Theme {
+    focus: Color,
+    border: Color,
+    matched: Color,
+    selection_fg: Color,
+    selection_bg: Color,
+    input: Color,
+    modal: Color,
+    dim: Color,
+    badge: Color,
+    error: Color,
+    /// プレビューの色の置き換え（置き換え前, 置き換え後）
+    preview_colors: &'static [(Color, Color)],
+    /// 色を使わずに修飾だけで描画するかどうか
+    monochrome: bool,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        preset_theme(ThemePreset::default())
+    }
+}
+
+/// プリセットの配色を取得する
+fn preset_theme(preset: ThemePreset) -> Theme {
+    match preset {
+        ThemePreset::Dark => Theme {
+            focus: Color::Cyan,
+            border: Color::White,
+            matched: Color::Yellow,
+            selection_fg: Color::Black,
+            selection_bg: Color::White,
+            input: Color::Yellow,
+            modal: Color::Yellow,
+            dim: Color::DarkGray,
+            badge: Color::Cyan,
+            error: Color::Red,
+            preview_colors: &[],
+            monochrome: false,
+        },
+        ThemePreset::Light => Theme {
+            focus: Color::Blue,
+            border: Color::Black,
+            matched: Color::Magenta,
+            selection_fg: Color::White,
+            selection_bg: Color::Blue,
+            input: Color::Blue,
+            modal: Color::Magenta,
+            dim: Color::Gray,
+            badge: Color::Blue,
+            error: Color::Red,
+            preview_colors: &[
+                (Color::White, Color::Black),
+                (Color::Gray, Color::DarkGray),
+                (Color::Yellow, Color::Indexed(136)),
+                (Color::LightYellow, Color::Indexed(136)),
+                (Color::Cyan, Color::Blue),
+                (Color::LightCyan, Color::Blue),
+            ],
+            monochrome: false,
+        },
+        ThemePreset::HighContrast => Theme {
+            focus: Color::LightCyan,
+            border: Color::White,
+            matched: Color::LightYellow,
+            selection_fg: Color::Black,
+            selection_bg: Color::LightYellow,
+            input: Color::White,
+            modal: Color::LightYellow,
+            dim: Color::Gray,
+            badge: Color::LightCyan,
+            error: Color::LightRed,
+            preview_colors: &[
+                (Color::DarkGray, Color::Gray),
+                (Color::Gray, Color::White),
+                (Color::Red, Color::LightRed),
+                (Color::Green, Color::LightGreen),
+                (Color::Yellow, Color::LightYellow),
+                (Color::Blue, Color::LightBlue),
+                (Color::Magenta, Color::LightMagenta),
+                (Color::Cyan, Color::LightCyan),
+            ],
+            monochrome: false,
+        },
+    }
+}
+
+impl Theme {
+    /// 設定から配色を作成する
+    ///
+    /// # Arguments
+    /// * `config` - 配色の設定
+    /// * `monochrome` - 色を使わずに描画するかどうか（`NO_COLOR`が設定されている場合など）
+    pub fn new(config: &ThemeConfig, monochrome: bool) -> Self {
+        let base = preset_theme(config.preset);
+        Self {
+            focus: config.focus.unwrap_or(base.focus),
+            border: config.border.unwrap_or(base.border),
+            matched: config.matched.unwrap_or(base.matched),
+            selection_fg: config.selection_fg.unwrap_or(base.selection_fg),
+            selection_bg: config.selection_bg.unwrap_or(base.selection_bg),
+            input: config.input.unwrap_or(base.input),
+            modal: config.modal.unwrap_or(base.modal),
+            dim: config.dim.unwrap_or(base.dim),
+            badge: config.badge.unwrap_or(base.badge),
+            error: config.error.unwrap_or(base.error),
+            monochrome,
+            ..base
+        }
+    }
+
+    /// パネルの枠のスタイル
+    pub fn panel(&self, focused: bool) -> Style {
+        if focused {
+            self.fg(self.focus).add_modifier(Modifier::BOLD)
+        } else {
+            self.fg(self.border)
+        }
+    }
+
+    /// 検索語にマッチした文字のスタイル
+    pub fn matched(&self) -> Style {
+        let style = self.fg(self.matched).add_modifier(Modifier::BOLD);
+        if self.monochrome {
+            style.add_modifier(Modifier::UNDERLINED)
+        } else {
+            style
+        }
+    }
+
+    /// 選択中の行のスタイル
+    pub fn selection(&self) -> Style {
+        if self.monochrome {
+            Style::default().add_modifier(Modifier::REVERSED | Modifier::BOLD)
+        } else {
+            Style::default()
+                .fg(self.selection_fg)
+                .bg(self.selection_bg)
+                .add_modifier(Modifier::BOLD)
+        }
+    }
+
+    /// 検索の入力欄のスタイル
+    pub fn input(&self) -> Style {
+        self.fg(self.input)
+    }
+
+    /// モーダルのスタイル
+    pub fn modal(&self) -> Style {
+        self.fg(self.modal).add_modifier(Modifier::BOLD)
+    }
+
+    /// 暗く表示する行のスタイル
+    pub fn dimmed(&self) -> Style {
+        if self.monochrome {
+            Style::default().add_modifier(Modifier::DIM)
+        } else {
+            self.fg(self.dim)
+        }
+    }
+
+    /// エントリの種類のバッジのスタイル
+    pub fn badge(&self) -> Style {
+        self.fg(self.badge)
+    }
+
+    /// 開けないパスの状態のスタイル
+    pub fn error(&self) -> Style {
+        if self.monochrome {
+            Style::default().add_modifier(Modifier::BOLD)
+        } else {
+            self.fg(self.error)
+        }
+    }
+
+    /// プレビューの色を配色に合わせて置き換える（モノクロの場合は色を取り除く）
+    pub fn apply(&self, lines: &[Line<'static>]) -> Vec<Line<'static>> {
+        if !self.monochrome && self.preview_colors.is_empty() {
+            return lines.to_vec();
+        }
+        lines
+            .iter()
+            .cloned()
+            .map(|mut line| {
+                line.style = self.recolor(line.style);
+                for span in &mut line.spans {
+                    span.style = self.recolor(span.style);
+                }
+                line
+            })
+            .collect()
+    }
+
+    /// 文字色だけを指定したスタイル（モノクロの場合は色を付けない）
+    fn fg(&self, color: Color) -> Style {
+        if self.monochrome {
+            Style::default()
+        } else {
+            Style::default().fg(color)
+        }
+    }
+
+    fn recolor(&self, mut style: Style) -> Style {
+        if self.monochrome {
+            style.fg = None;
+            style.bg = None;
+            style.underline_color = None;
+            return style;
+        }
+        let replace = |color: Color| {
+            self.preview_colors
+                .iter()
+                .find(|(from, _)| *from == color)
+                .map_or(color, |(_, to)| *to)
+        };
+        style.fg = style.fg.map(replace);
+        style.bg = style.bg.map(replace);
+        style
+    }
+}
+
+/// `NO_COLOR`環境変数で色を使わないよう指定されているかどうか
+///
+/// <https://no-color.org/> に従い、空でない値が設定されている場合に`true`を返す
+pub fn no_color() -> bool {
+    std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::text::Span;
+
+    #[test]
+    fn test_new_要素ごとの色でプリセットを上書きできる() {
+        // Arrange
+        let config = ThemeConfig {
+            preset: ThemePreset::Light,
+            matched: Some(Color::Red),
+            ..ThemeConfig::default()
+        };
+
+        // Act
+        let theme = Theme::new(&config, false);
+
+        // Assert
+        assert_eq!(theme.matched().fg, Some(Color::Red));
+        assert_eq!(theme.panel(true).fg, Some(Color::Blue));
+    }
+
+    #[test]
+    fn test_apply_プリセットに合わせてプレビューの色を置き換える() {
+        // Arrange
+        let config = ThemeConfig {
+            preset: ThemePreset::Light,
+            ..ThemeConfig::default()
+        };
+        let theme = Theme::new(&config, false);
+        let lines = vec![Line::from(vec![
+            Span::styled("a", Style::default().fg(Color::White)),
+            Span::styled("b", Style::default().fg(Color::Green)),
+        ])];
+
+        // Act
+        let applied = theme.apply(&lines);
+
+        // Assert
+        assert_eq!(applied[0].spans[0].style.fg, Some(Color::Black));
+        assert_eq!(applied[0].spans[1].style.fg, Some(Color::Green));
+    }
+
+    #[test]
+    fn test_apply_モノクロの場合は色を取り除き修飾は残す() {
+        // Arrange
+        let theme = Theme::new(&ThemeConfig::default(), true);
+        let lines = vec![Line::from(Span::styled(
+            "a",
+            Style::default()
+                .fg(Color::Rgb(1, 2, 3))
+                .bg(Color::Blue)
+                .add_modifier(Modifier::BOLD),
+        ))];
+
+        // Act
+        let applied = theme.apply(&lines);
+
+        // Assert
+        let style = applied[0].spans[0].style;
+        assert_eq!(style.fg, None);
+        assert_eq!(style.bg, None);
+        assert!(style.add_modifier.contains(Modifier::BOLD));
+        assert_eq!(theme.panel(true).fg, None);
+        assert!(theme.selection().add_modifier.contains(Modifier::REVERSED));
+    }
+}
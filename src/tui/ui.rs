@@ -1,6 +1,6 @@
 use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect},
-    style::{Color, Modifier, Style},
+    style::Style,
     text::{Line, Span},
     widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph},
     Frame,
@@ -8,8 +8,18 @@ use ratatui::{
 
 use super::app::App;
 use super::focus::{FocusedPanel, ModalFocus};
+use super::keymap::{Action, KeyScope};
 use crate::Pavo;
 
+/// 下の検索欄の高さ（枠線を含む）
+const SEARCH_HEIGHT: u16 = 3;
+
+/// PathsとPreviewパネルに表示できる行数を画面の大きさから求める
+pub fn page_height(area: Rect) -> usize {
+    // 検索欄とパネルの上下の枠線を除く
+    usize::from(area.height.saturating_sub(SEARCH_HEIGHT + 2))
+}
+
 /// UIを描画する
 ///
 /// # Arguments
@@ -19,7 +29,7 @@ use crate::Pavo;
 pub fn ui(f: &mut Frame, app: &App, pavo: &Pavo) {
     let chunks = Layout::default()
         .direction(Direction::Vertical)
-        .constraints([Constraint::Min(0), Constraint::Length(3)])
+        .constraints([Constraint::Min(0), Constraint::Length(SEARCH_HEIGHT)])
         .split(f.area());
 
     let top_chunks = Layout::default()
@@ -27,35 +37,54 @@ pub fn ui(f: &mut Frame, app: &App, pavo: &Pavo) {
         .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
         .split(chunks[0]);
 
-    // 次のパネル名を取得
-    let next_panel_name = app.focused_panel().next().name();
+    // 次のパネルへの移動のヘルプ
+    let focused_scope = KeyScope::from(app.focused_panel());
+    let next_panel_label = format!("→ {}", app.focused_panel().next().name());
+    let next_panel_hint = key_hints(
+        app,
+        focused_scope,
+        &[(&[Action::FocusNext], &next_panel_label)],
+    );
 
     // プレビューエリア (左)
     let preview_title = if app.focused_panel() == FocusedPanel::Preview {
+        let config = app.preview_config();
         format!(
-            "{} [Tab → {}]",
+            "{} (depth {}, lines {}{}{}){}{}",
             FocusedPanel::Preview.name(),
-            next_panel_name
+            config.tree_depth,
+            config.file_lines,
+            if config.show_hidden { ", hidden" } else { "" },
+            if app.preview_wrap() { "" } else { ", nowrap" },
+            key_hints(
+                app,
+                KeyScope::Preview,
+                &[
+                    (&[Action::FocusSearch], "search"),
+                    (&[Action::IncreaseDepth, Action::DecreaseDepth], "depth"),
+                    (&[Action::ToggleHidden], "hidden"),
+                    (&[Action::ToggleWrap], "wrap"),
+                    (&[Action::ShowMore], "more"),
+                ]
+            ),
+            next_panel_hint
         )
     } else {
         FocusedPanel::Preview.name().to_string()
     };
-    let preview_style = if app.focused_panel() == FocusedPanel::Preview {
-        Style::default()
-            .fg(Color::Cyan)
-            .add_modifier(Modifier::BOLD)
-    } else {
-        Style::default().fg(Color::White)
-    };
+    let theme = app.theme();
+    let preview_style = theme.panel(app.focused_panel() == FocusedPanel::Preview);
     let preview_block = Block::default()
         .title(preview_title)
         .borders(Borders::ALL)
         .style(preview_style);
 
-    let preview_text = Paragraph::new(app.preview().to_vec())
+    let mut preview_text = Paragraph::new(theme.apply(app.preview()))
         .block(preview_block)
-        .wrap(ratatui::widgets::Wrap { trim: false })
         .scroll((app.preview_scroll(), 0));
+    if app.preview_wrap() {
+        preview_text = preview_text.wrap(ratatui::widgets::Wrap { trim: false });
+    }
     f.render_widget(preview_text, top_chunks[0]);
 
     // パス一覧エリア (右)
@@ -80,8 +109,21 @@ pub fn ui(f: &mut Frame, app: &App, pavo: &Pavo) {
                 })
                 .unwrap_or_default();
 
-            // マッチ位置をハイライト
+            // ワークツリーやサブモジュールの子の行と、開けないパスは暗く表示する
+            let state = app.path_state(idx);
+            let base_style = if app.is_linked(idx) || !state.is_available() {
+                theme.dimmed()
+            } else {
+                Style::default()
+            };
+
+            // 種類のアイコンはマッチ位置の計算に含めないよう、表示パスとは別のSpanにする
             let mut spans = Vec::new();
+            if let Some(icon) = app.icon(idx) {
+                spans.push(Span::styled(icon, base_style));
+            }
+
+            // マッチ位置をハイライト
             let chars: Vec<char> = display_path.chars().collect();
             let mut last_idx = 0;
 
@@ -89,17 +131,12 @@ pub fn ui(f: &mut Frame, app: &App, pavo: &Pavo) {
                 // マッチしていない部分
                 if last_idx < match_idx {
                     let unmatched: String = chars[last_idx..match_idx].iter().collect();
-                    spans.push(Span::raw(unmatched));
+                    spans.push(Span::styled(unmatched, base_style));
                 }
 
                 // マッチした部分をハイライト
                 if match_idx < chars.len() {
-                    spans.push(Span::styled(
-                        chars[match_idx].to_string(),
-                        Style::default()
-                            .fg(Color::Yellow)
-                            .add_modifier(Modifier::BOLD),
-                    ));
+                    spans.push(Span::styled(chars[match_idx].to_string(), theme.matched()));
                     last_idx = match_idx + 1;
                 }
             }
@@ -107,33 +144,51 @@ pub fn ui(f: &mut Frame, app: &App, pavo: &Pavo) {
             // 残りの部分
             if last_idx < chars.len() {
                 let remaining: String = chars[last_idx..].iter().collect();
-                spans.push(Span::raw(remaining));
+                spans.push(Span::styled(remaining, base_style));
             }
 
-            // persist_markとtags_displayを追加
+            // エントリの種類のバッジ、persist_mark、tags_displayを追加
+            for badge in app.badges(idx) {
+                spans.push(Span::styled(format!(" [{}]", badge), theme.badge()));
+            }
             if !persist_mark.is_empty() {
                 spans.push(Span::raw(persist_mark));
             }
             if !tags_display.is_empty() {
                 spans.push(Span::raw(tags_display));
             }
+            if app.is_expanded(idx) {
+                spans.push(Span::raw(" ▾"));
+            }
+            if !state.is_available() {
+                spans.push(Span::styled(format!(" [{}]", state.name()), theme.error()));
+            }
 
             ListItem::new(Line::from(spans))
         })
         .collect();
 
     let paths_title = if app.focused_panel() == FocusedPanel::Paths {
-        format!("{} [Tab → {}]", FocusedPanel::Paths.name(), next_panel_name)
+        format!(
+            "{}{}{}",
+            FocusedPanel::Paths.name(),
+            key_hints(
+                app,
+                KeyScope::Paths,
+                &[
+                    (&[Action::FocusSearch], "search"),
+                    (
+                        &[Action::Expand, Action::Collapse],
+                        "worktrees & submodules"
+                    )
+                ]
+            ),
+            next_panel_hint
+        )
     } else {
         FocusedPanel::Paths.name().to_string()
     };
-    let paths_style = if app.focused_panel() == FocusedPanel::Paths {
-        Style::default()
-            .fg(Color::Cyan)
-            .add_modifier(Modifier::BOLD)
-    } else {
-        Style::default().fg(Color::White)
-    };
+    let paths_style = theme.panel(app.focused_panel() == FocusedPanel::Paths);
 
     let list = List::new(items)
         .block(
@@ -142,12 +197,7 @@ pub fn ui(f: &mut Frame, app: &App, pavo: &Pavo) {
                 .borders(Borders::ALL)
                 .style(paths_style),
         )
-        .highlight_style(
-            Style::default()
-                .fg(Color::Black)
-                .bg(Color::White)
-                .add_modifier(Modifier::BOLD),
-        )
+        .highlight_style(theme.selection())
         .highlight_symbol("> ");
 
     let mut state = ListState::default();
@@ -155,22 +205,24 @@ pub fn ui(f: &mut Frame, app: &App, pavo: &Pavo) {
     f.render_stateful_widget(list, top_chunks[1], &mut state);
 
     // 入力エリア (下)
+    // 検索モード（Ctrl-t: アルゴリズム, Ctrl-s: 大文字小文字）を表示
+    let search_mode = format!(
+        "({}, {})",
+        app.matcher().algorithm().name(),
+        app.matcher().case().name()
+    );
     let search_title = if app.focused_panel() == FocusedPanel::Search {
         format!(
-            "{} [Tab → {}]",
+            "{} {}{}{}",
             FocusedPanel::Search.name(),
-            next_panel_name
+            search_mode,
+            key_hints(app, KeyScope::Search, &[(&[Action::FocusPaths], "normal")]),
+            next_panel_hint
         )
     } else {
-        FocusedPanel::Search.name().to_string()
-    };
-    let search_style = if app.focused_panel() == FocusedPanel::Search {
-        Style::default()
-            .fg(Color::Cyan)
-            .add_modifier(Modifier::BOLD)
-    } else {
-        Style::default().fg(Color::White)
+        format!("{} {}", FocusedPanel::Search.name(), search_mode)
     };
+    let search_style = theme.panel(app.focused_panel() == FocusedPanel::Search);
     let input_block = Block::default()
         .title(search_title)
         .borders(Borders::ALL)
@@ -178,7 +230,7 @@ pub fn ui(f: &mut Frame, app: &App, pavo: &Pavo) {
 
     let input_text = Paragraph::new(app.input())
         .block(input_block)
-        .style(Style::default().fg(Color::Yellow));
+        .style(theme.input());
     f.render_widget(input_text, chunks[1]);
 
     // Searchパネルがフォーカスされている場合、カーソルを表示
@@ -243,23 +295,27 @@ fn draw_modal(f: &mut Frame, app: &App) {
         "Path: {}\n\n\
 {} {} Persist\n\
 {} Tags: {}\n\n\
-[Tab] Switch field  [↑/↓/Space] Toggle (Persist)\n\
-[Enter] Save  [Esc] Cancel",
+{}\n\
+{}",
         path_display,
         persist_indicator,
         persist_checkbox,
         tags_indicator,
-        app.modal_tags_input()
+        app.modal_tags_input(),
+        modal_hints(
+            app,
+            &[
+                (Action::NextField, "Switch field"),
+                (Action::TogglePersist, "Toggle (Persist)")
+            ]
+        ),
+        modal_hints(app, &[(Action::Save, "Save"), (Action::Cancel, "Cancel")])
     );
 
     let modal_block = Block::default()
         .title("Path Setting")
         .borders(Borders::ALL)
-        .style(
-            Style::default()
-                .fg(Color::Yellow)
-                .add_modifier(Modifier::BOLD),
-        );
+        .style(app.theme().modal());
 
     let modal_paragraph = Paragraph::new(modal_text)
         .block(modal_block)
@@ -276,3 +332,36 @@ fn draw_modal(f: &mut Frame, app: &App) {
         f.set_cursor_position((cursor_x, cursor_y));
     }
 }
+
+/// パネルのタイトルに表示するヘルプを、操作に割り当てられた最初のキーから作成する
+///
+/// 割り当てのない操作は表示せず、表示するものがない場合は空文字列を返す
+fn key_hints(app: &App, scope: KeyScope, hints: &[(&[Action], &str)]) -> String {
+    let parts: Vec<String> = hints
+        .iter()
+        .filter_map(|(actions, label)| {
+            let keys: Vec<String> = actions
+                .iter()
+                .filter_map(|action| app.keymap().key(scope, *action))
+                .collect();
+            (!keys.is_empty()).then(|| format!("{} {}", keys.join("/"), label))
+        })
+        .collect();
+    if parts.is_empty() {
+        String::new()
+    } else {
+        format!(" [{}]", parts.join(", "))
+    }
+}
+
+/// モーダルに表示するヘルプを、操作に割り当てられた全てのキーから作成する
+fn modal_hints(app: &App, hints: &[(Action, &str)]) -> String {
+    hints
+        .iter()
+        .filter_map(|(action, label)| {
+            let keys = app.keymap().keys(KeyScope::Modal, *action);
+            (!keys.is_empty()).then(|| format!("[{}] {}", keys, label))
+        })
+        .collect::<Vec<_>>()
+        .join("  ")
+}